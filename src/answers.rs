@@ -0,0 +1,129 @@
+use crate::input::{day_dir, Source};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// The recorded answers for a day, stored one per line with part one first.
+/// A blank line marks a part whose answer isn't known yet.
+#[derive(Default, Debug, PartialEq)]
+pub struct Answers {
+    parts: [Option<String>; 2],
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn parse(contents: &str) -> Answers {
+        let mut lines = contents.lines().map(|line| {
+            let line = line.trim();
+            (!line.is_empty()).then(|| line.to_string())
+        });
+        Answers {
+            parts: [lines.next().flatten(), lines.next().flatten()],
+        }
+    }
+
+    /// The answers file for a day's input: `answers.txt` for the puzzle input and
+    /// `example_N.answers.txt` for an example. Inputs from anywhere else have no answers file.
+    pub fn path(day: &u8, source: &Source) -> Option<PathBuf> {
+        match source {
+            Source::Puzzle => Some(day_dir(day).join("answers.txt")),
+            Source::Example(number) => Some(day_dir(day).join(Answers::example_name(*number))),
+            Source::Path(_) | Source::Stdin => None,
+        }
+    }
+
+    pub fn example_name(number: u8) -> String {
+        format!("example_{}.answers.txt", number)
+    }
+
+    /// Loads an answers file, treating a missing file as no known answers.
+    pub fn load(path: &Path) -> io::Result<Answers> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(Answers::parse(&contents)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        self.parts.get(part as usize - 1)?.as_deref()
+    }
+
+    pub fn set(&mut self, part: u8, answer: &str) {
+        self.parts[part as usize - 1] = Some(answer.to_string());
+    }
+
+    pub fn check(&self, part: u8, answer: &str) -> Verdict {
+        match self.get(part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for part in &self.parts {
+            writeln!(f, "{}", part.as_deref().unwrap_or(""))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse("\n31\n");
+        let cases = vec![
+            (1, "11", Verdict::Unknown),
+            (2, "31", Verdict::Pass),
+            (
+                2,
+                "30",
+                Verdict::Fail {
+                    expected: "31".to_string(),
+                },
+            ),
+        ];
+        for (part, answer, expected) in cases {
+            assert_eq!(answers.check(part, answer), expected)
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = Answers::default();
+        answers.set(2, "31");
+        assert_eq!(answers.to_string(), "\n31\n");
+        assert_eq!(Answers::parse(&answers.to_string()), answers);
+    }
+}
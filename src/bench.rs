@@ -0,0 +1,203 @@
+use crate::days::Day;
+use crate::error;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use std::{fs, io};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Stats {
+        let mut samples = samples.to_vec();
+        samples.sort();
+        let count = samples.len().max(1) as u32;
+        let middle = samples.len() / 2;
+        let median = match samples.len() {
+            0 => Duration::ZERO,
+            len if len % 2 == 0 => (samples[middle - 1] + samples[middle]) / 2,
+            _ => samples[middle],
+        };
+        let mean = samples.iter().sum::<Duration>() / count;
+        let variance = samples
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / count as f64;
+        // Nearest-rank percentile
+        let p95_rank = (samples.len() as f64 * 0.95).ceil() as usize;
+        Stats {
+            min: samples.first().copied().unwrap_or_default(),
+            median,
+            mean,
+            p95: samples
+                .get(p95_rank.saturating_sub(1))
+                .copied()
+                .unwrap_or_default(),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:?}, median {:?}, mean {:?}, p95 {:?}, stddev {:?}",
+            self.min, self.median, self.mean, self.p95, self.stddev
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Benchmark {
+    pub day: u8,
+    pub part: u8,
+    pub iterations: usize,
+    /// The time for the whole part, parsing included
+    pub solve: Stats,
+    /// The time for parsing alone, if it was asked for and the day parses separately
+    pub parse: Option<Stats>,
+}
+
+/// Runs `warmup` untimed iterations of a part, then times `iterations` more. Parsing is only
+/// timed on its own if `time_parse` is set and the day has a separate parsing step.
+pub fn bench(
+    day: &dyn Day,
+    (number, part): (u8, u8),
+    input: &str,
+    warmup: usize,
+    iterations: usize,
+    time_parse: bool,
+) -> error::Result<Benchmark> {
+    let solve = |input: &str| match part {
+        1 => day.part_one(input),
+        _ => day.part_two(input),
+    };
+    let time = |run: &dyn Fn() -> error::Result<()>| -> error::Result<Stats> {
+        for _ in 0..warmup {
+            run()?;
+        }
+        let samples = (0..iterations)
+            .map(|_| {
+                let start = Instant::now();
+                run().map(|_| start.elapsed())
+            })
+            .collect::<error::Result<Vec<Duration>>>()?;
+        Ok(Stats::new(&samples))
+    };
+
+    let parse = match time_parse && day.parse(input).is_some() {
+        true => Some(time(&|| day.parse(input).unwrap_or(Ok(())))?),
+        false => None,
+    };
+    Ok(Benchmark {
+        day: number,
+        part,
+        iterations,
+        solve: time(&|| solve(input).map(|_| ()))?,
+        parse,
+    })
+}
+
+/// Benchmarks saved to disk to compare later runs against.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct Baseline {
+    pub benchmarks: Vec<Benchmark>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Baseline> {
+        serde_json::from_str(&fs::read_to_string(path)?).map_err(io::Error::other)
+    }
+
+    /// Adds a benchmark to the baseline at `path`, replacing any earlier run of the same part.
+    pub fn save(path: &Path, benchmark: &Benchmark) -> io::Result<()> {
+        let mut baseline = match Baseline::load(path) {
+            Ok(baseline) => baseline,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Baseline::default(),
+            Err(err) => return Err(err),
+        };
+        baseline
+            .benchmarks
+            .retain(|other| (other.day, other.part) != (benchmark.day, benchmark.part));
+        baseline.benchmarks.push(benchmark.clone());
+        baseline
+            .benchmarks
+            .sort_by_key(|other| (other.day, other.part));
+        let json = serde_json::to_string_pretty(&baseline).map_err(io::Error::other)?;
+        fs::write(path, json + "\n")
+    }
+
+    pub fn find(&self, day: u8, part: u8) -> Option<&Benchmark> {
+        self.benchmarks
+            .iter()
+            .find(|benchmark| (benchmark.day, benchmark.part) == (day, part))
+    }
+}
+
+/// The relative change in median solve time from `baseline` to `current`, as a percentage.
+pub fn median_change(baseline: &Benchmark, current: &Benchmark) -> f64 {
+    let before = baseline.solve.median.as_secs_f64();
+    let after = current.solve.median.as_secs_f64();
+    match before {
+        0.0 => 0.0,
+        _ => (after - before) / before * 100.0,
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = [4, 1, 3, 2, 20].map(Duration::from_millis).to_vec();
+        let stats = Stats::new(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(6));
+        assert_eq!(stats.p95, Duration::from_millis(20));
+        assert_eq!(stats.stddev.as_millis(), 7);
+    }
+
+    struct Unparsed;
+
+    impl Day for Unparsed {
+        fn part_one(&self, input: &str) -> error::Result<String> {
+            Ok(input.to_string())
+        }
+
+        fn part_two(&self, input: &str) -> error::Result<String> {
+            Ok(input.to_string())
+        }
+    }
+
+    #[test]
+    fn test_parse_timing() {
+        let parsed = crate::days::get_day(&1).unwrap();
+        let cases = vec![
+            (parsed.as_ref(), true, true),
+            (parsed.as_ref(), false, false),
+            (&Unparsed as &dyn Day, true, false),
+        ];
+        for (day, time_parse, expected) in cases {
+            let benchmark = bench(day, (1, 1), "1   2", 0, 2, time_parse).unwrap();
+            assert_eq!(benchmark.parse.is_some(), expected);
+        }
+    }
+
+    #[test]
+    fn test_even_median() {
+        let samples: Vec<Duration> = [4, 1, 3, 2].map(Duration::from_millis).to_vec();
+        assert_eq!(Stats::new(&samples).median, Duration::from_micros(2500));
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::{env, fs, io};
+
+const DEFAULT_PATH: &str = "aoc24.toml";
+
+/// Settings read from `aoc24.toml` in the working directory, or the file named by `AOC_CONFIG`.
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Directory holding the `day_N` input directories
+    pub input_dir: Option<PathBuf>,
+    /// Session cookie used to fetch missing inputs
+    pub session: Option<String>,
+    /// Server to fetch missing inputs from, e.g. a local stand-in for adventofcode.com
+    pub base_url: Option<String>,
+    /// Number of threads to run days and parts on, by default one per core
+    pub threads: Option<usize>,
+}
+
+impl Config {
+    pub fn load() -> Result<Config, String> {
+        let path = env::var_os("AOC_CONFIG")
+            .map(PathBuf::from)
+            .unwrap_or(PathBuf::from(DEFAULT_PATH));
+        match fs::read_to_string(&path) {
+            Ok(contents) => Config::parse(&contents)
+                .map_err(|err| format!("Invalid config {}: {}", path.display(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(format!("Couldn't read config {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// The config for this run, loaded on first use. A broken config file is reported once
+    /// and otherwise ignored.
+    pub fn global() -> &'static Config {
+        static CONFIG: OnceLock<Config> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            Config::load().unwrap_or_else(|message| {
+                eprintln!("Warning: {}", message);
+                Config::default()
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Config::parse("input_dir = \"/tmp/aoc\"").unwrap(),
+            Config {
+                input_dir: Some(PathBuf::from("/tmp/aoc")),
+                ..Config::default()
+            }
+        );
+        assert_eq!(Config::parse("threads = 2").unwrap().threads, Some(2));
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert!(Config::parse("input = 3").is_err());
+    }
+}
@@ -1,3 +1,6 @@
+use crate::error;
+use crate::viz::Visualize;
+
 mod eight;
 mod eleven;
 mod fifteen;
@@ -15,28 +18,103 @@ mod twelve;
 mod two;
 
 pub trait Day {
-    fn part_one(&self, input: &str) -> String;
-    fn part_two(&self, input: &str) -> String;
+    fn part_one(&self, input: &str) -> error::Result<String>;
+    fn part_two(&self, input: &str) -> error::Result<String>;
+
+    /// Parses the input without solving it, so benchmarks can time parsing on its own.
+    /// Days without a separate parsing step return `None`.
+    fn parse(&self, _input: &str) -> Option<error::Result<()>> {
+        None
+    }
+
+    /// The day's visualization, for days that implement [`Visualize`].
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        None
+    }
+}
+
+pub struct Registration {
+    pub number: u8,
+    pub title: &'static str,
+    /// How many parts the puzzle has, 1 for day 25 and 2 otherwise
+    pub parts: u8,
+    new: fn() -> Box<dyn Day>,
+}
+
+impl Registration {
+    pub fn day(&self) -> Box<dyn Day> {
+        (self.new)()
+    }
+}
+
+/// Adds each day, whose module is declared above, to `DAYS`. Days with a single part are
+/// registered with a trailing `parts: 1`.
+macro_rules! register_days {
+    (@parts) => { 2 };
+    (@parts $parts:literal) => { $parts };
+    ($($number:literal => $module:ident::$day:ident, $title:literal $(, parts: $parts:literal)?;)*) => {
+        static DAYS: &[Registration] = &[
+            $(Registration {
+                number: $number,
+                title: $title,
+                parts: register_days!(@parts $($parts)?),
+                new: || -> Box<dyn Day> { Box::new($module::$day::default()) },
+            },)*
+        ];
+    };
+}
+
+register_days! {
+    1 => one::DayOne, "Historian Hysteria";
+    2 => two::DayTwo, "Red-Nosed Reports";
+    3 => three::DayThree, "Mull It Over";
+    4 => four::DayFour, "Ceres Search";
+    5 => five::DayFive, "Print Queue";
+    6 => six::DaySix, "Guard Gallivant";
+    7 => seven::DaySeven, "Bridge Repair";
+    8 => eight::DayEight, "Resonant Collinearity";
+    9 => nine::DayNine, "Disk Fragmenter";
+    10 => ten::DayTen, "Hoof It";
+    11 => eleven::DayEleven, "Plutonian Pebbles";
+    12 => twelve::DayTwelve, "Garden Groups";
+    13 => thirteen::DayThirteen, "Claw Contraption";
+    14 => fourteen::DayFourteen, "Restroom Redoubt";
+    15 => fifteen::DayFifteen, "Warehouse Woes";
+}
+
+/// Every registered day, in day order.
+pub fn registered_days() -> impl Iterator<Item = &'static Registration> {
+    DAYS.iter()
+}
+
+pub fn get_registration(day: &u8) -> Result<&'static Registration, String> {
+    registered_days()
+        .find(|registration| registration.number == *day)
+        .ok_or_else(|| format!("Day {} not supported.", day))
 }
 
 pub fn get_day(day: &u8) -> Result<Box<dyn Day>, String> {
-    match day {
-        1 => Ok(Box::new(one::DayOne::default())),
-        2 => Ok(Box::new(two::DayTwo::default())),
-        3 => Ok(Box::new(three::DayThree::default())),
-        4 => Ok(Box::new(four::DayFour::default())),
-        5 => Ok(Box::new(five::DayFive::default())),
-        6 => Ok(Box::new(six::DaySix::default())),
-        7 => Ok(Box::new(seven::DaySeven::default())),
-        8 => Ok(Box::new(eight::DayEight::default())),
-        9 => Ok(Box::new(nine::DayNine::default())),
-        10 => Ok(Box::new(ten::DayTen::default())),
-        11 => Ok(Box::new(eleven::DayEleven::default())),
-        12 => Ok(Box::new(twelve::DayTwelve::default())),
-        13 => Ok(Box::new(thirteen::DayThirteen::default())),
-        14 => Ok(Box::new(fourteen::DayFourteen::default())),
-        15 => Ok(Box::new(fifteen::DayFifteen::default())),
-
-        _ => Err(format!("Day {} not supported.", day)),
+    get_registration(day).map(Registration::day)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered() {
+        let numbers: Vec<u8> = registered_days().map(|day| day.number).collect();
+        assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(numbers.iter().all(|number| (1..=25).contains(number)));
+        assert!(registered_days().all(|day| day.parts == if day.number == 25 { 1 } else { 2 }));
+    }
+
+    #[test]
+    fn test_get_day() {
+        assert!(get_day(&1).is_ok());
+        assert_eq!(
+            get_day(&26).err(),
+            Some("Day 26 not supported.".to_string())
+        );
     }
 }
@@ -1,126 +1,95 @@
 use crate::days::Day;
+use crate::error::Result;
+use crate::utils::{Coord, Grid};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
-#[derive(Eq, Hash, PartialEq, Clone, Debug)]
-struct Coord {
-    x: i32,
-    y: i32,
-}
+#[derive(Default)]
+pub struct DayEight {}
 
-impl Coord {
-    fn plus(&self, other: &Coord) -> Coord {
-        Coord {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
+impl DayEight {
+    fn in_bounds(coord: &Coord, map_size: &Coord) -> bool {
+        (0..map_size.x).contains(&coord.x) && (0..map_size.y).contains(&coord.y)
     }
 
-    fn minus(&self, other: &Coord) -> Coord {
-        Coord {
-            x: self.x - other.x,
-            y: self.y - other.y,
-        }
+    /// The antinodes on either side of a pair of antennas, which may lie outside the map.
+    fn find_antinodes(antenna_one: &Coord, antenna_two: &Coord) -> HashSet<Coord> {
+        let delta = *antenna_two - *antenna_one;
+        HashSet::from([*antenna_one - delta, *antenna_two + delta])
     }
-}
-
-#[derive(Default)]
-pub struct DayEight {}
 
-impl DayEight {
-    fn find_antinodes(antenna_one: &Coord, antenna_two: &Coord, br: &Coord) -> HashSet<Coord> {
-        let mut antinodes = HashSet::from([antenna_one.clone(), antenna_two.clone()]);
-
-        let delta_coord = Coord {
-            x: antenna_two.x - antenna_one.x,
-            y: antenna_two.y - antenna_one.y,
-        };
-        let mut next_coord = antenna_one.minus(&delta_coord);
-        while next_coord.x < br.x && next_coord.x >= 0 && next_coord.y < br.y && next_coord.y >= 0 {
-            antinodes.insert(next_coord.clone());
-            next_coord = next_coord.minus(&delta_coord);
-        }
-        next_coord = antenna_two.plus(&delta_coord);
-        while next_coord.x < br.x && next_coord.x >= 0 && next_coord.y < br.y && next_coord.y >= 0 {
-            antinodes.insert(next_coord.clone());
-            next_coord = next_coord.plus(&delta_coord);
+    /// Every point on the map in line with a pair of antennas, at any multiple of their distance.
+    fn find_resonant_antinodes(
+        antenna_one: &Coord,
+        antenna_two: &Coord,
+        map_size: &Coord,
+    ) -> HashSet<Coord> {
+        let delta = *antenna_two - *antenna_one;
+        let mut antinodes = HashSet::new();
+        for (start, step) in [(*antenna_one, -delta), (*antenna_two, delta)] {
+            let mut next_coord = start;
+            while Self::in_bounds(&next_coord, map_size) {
+                antinodes.insert(next_coord);
+                next_coord += step;
+            }
         }
         antinodes
     }
-    fn find_antenna(input: &str) -> HashMap<char, HashSet<Coord>> {
-        let mut antennae_positions: HashMap<char, HashSet<Coord>> = HashMap::new();
-        for (y, line) in input
-            .lines()
-            .enumerate()
-            .map(|(index, item)| (index as i32, item))
-        {
-            for (x, space) in line
-                .chars()
-                .enumerate()
-                .map(|(index, item)| (index as i32, item))
-            {
-                match space {
-                    '.' => (),
-                    _ => {
-                        antennae_positions
-                            .entry(space)
-                            .or_default()
-                            .insert(Coord { x, y });
-                    }
-                }
+
+    /// The size of the map, and where the antennas of each frequency are on it.
+    fn parse_map(input: &str) -> Result<(Coord, HashMap<char, HashSet<Coord>>)> {
+        let grid = Grid::parse(input, "", |space| match space {
+            '.' => Some(None),
+            _ if space.is_ascii_alphanumeric() => Some(Some(space)),
+            _ => None,
+        })?
+        .grid;
+        let mut antennae: HashMap<char, HashSet<Coord>> = HashMap::new();
+        for (position, frequency) in grid.iter() {
+            if let Some(frequency) = frequency {
+                antennae.entry(*frequency).or_default().insert(position);
             }
         }
-        antennae_positions
+        Ok((Coord::new(grid.width(), grid.height()), antennae))
     }
 
-    fn find_all_antinodes(map_size: &Coord, antennae: &HashMap<char, HashSet<Coord>>) -> u32 {
+    fn find_all_antinodes(
+        map_size: &Coord,
+        antennae: &HashMap<char, HashSet<Coord>>,
+        resonant: bool,
+    ) -> u32 {
         antennae
             .values()
             .flat_map(|positions| {
                 positions
                     .iter()
                     .permutations(2)
-                    .flat_map(|pair| DayEight::find_antinodes(pair[0], pair[1], map_size))
-            })
-            .filter(|coord| {
-                coord.x < map_size.x && coord.x >= 0 && coord.y < map_size.y && coord.y >= 0
+                    .flat_map(|pair| match resonant {
+                        true => DayEight::find_resonant_antinodes(pair[0], pair[1], map_size),
+                        false => DayEight::find_antinodes(pair[0], pair[1]),
+                    })
             })
+            .filter(|coord| Self::in_bounds(coord, map_size))
             .collect::<HashSet<Coord>>()
             .len() as u32
     }
 }
 
 impl Day for DayEight {
-    fn part_one(&self, input: &str) -> String {
-        let positions = DayEight::find_antenna(input);
-        let (x, y) = (
-            input.lines().collect::<Vec<_>>()[0].len(),
-            input.lines().count(),
-        );
-        DayEight::find_all_antinodes(
-            &Coord {
-                x: x as i32,
-                y: y as i32,
-            },
-            &positions,
-        )
-        .to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let (map_size, antennae) = DayEight::parse_map(input)?;
+        let count = DayEight::find_all_antinodes(&map_size, &antennae, false);
+        Ok(count.to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let positions = DayEight::find_antenna(input);
-        let (x, y) = (
-            input.lines().collect::<Vec<_>>()[0].len(),
-            input.lines().count(),
-        );
-        DayEight::find_all_antinodes(
-            &Coord {
-                x: x as i32,
-                y: y as i32,
-            },
-            &positions,
-        )
-        .to_string()
+    fn part_two(&self, input: &str) -> Result<String> {
+        let (map_size, antennae) = DayEight::parse_map(input)?;
+        let count = DayEight::find_all_antinodes(&map_size, &antennae, true);
+        Ok(count.to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayEight::parse_map(input).map(|_| ()))
     }
 }
 
@@ -128,25 +97,55 @@ impl Day for DayEight {
 pub mod test {
     use super::*;
 
+    #[test]
+    fn test_parse_map() {
+        let (map_size, antennae) = DayEight::parse_map("a..\n.0a\n\n\n").unwrap();
+        assert_eq!(map_size, Coord::new(3, 2));
+        assert_eq!(
+            antennae[&'a'],
+            HashSet::from([Coord::new(0, 0), Coord::new(2, 1)])
+        );
+
+        let cases = vec![
+            ("a..\n.#a", "line 2, column 2: unexpected '#'"),
+            (
+                "a..\n.a",
+                "line 2, column 1: expected a row 3 cells wide, found 2",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                DayEight::parse_map(input).err().map(|err| err.to_string()),
+                Some(expected.to_string())
+            );
+        }
+    }
+
     #[test]
     fn test_find_antinode() {
         let cases = vec![(
-            Coord { x: 0, y: 0 },
-            Coord { x: 1, y: 1 },
-            HashSet::from_iter(
-                [Coord { x: -1, y: -1 }, Coord { x: 2, y: 2 }]
-                    .iter()
-                    .cloned(),
-            ),
+            Coord::new(0, 0),
+            Coord::new(1, 1),
+            HashSet::from([Coord::new(-1, -1), Coord::new(2, 2)]),
         )];
         for (left, right, expected) in cases {
-            assert_eq!(
-                DayEight::find_antinodes(&left, &right, &Coord { x: 3, y: 3 }),
-                expected
-            )
+            assert_eq!(DayEight::find_antinodes(&left, &right), expected)
         }
     }
 
+    #[test]
+    fn test_find_resonant_antinodes() {
+        let antinodes = DayEight::find_resonant_antinodes(
+            &Coord::new(1, 0),
+            &Coord::new(2, 1),
+            &Coord::new(4, 4),
+        );
+        assert_eq!(
+            antinodes,
+            HashSet::from([Coord::new(1, 0), Coord::new(2, 1), Coord::new(3, 2)])
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let day = DayEight::default();
@@ -166,10 +165,29 @@ pub mod test {
             14,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
+    /// Part one only counts the antinode on either side of each pair, not every point in line
+    /// with them as part two does.
+    #[test]
+    fn test_direct_antinodes() {
+        let day = DayEight::default();
+        let input = r#"..........
+..........
+..........
+....a.....
+..........
+.....a....
+..........
+..........
+..........
+.........."#;
+        assert_eq!(day.part_one(input), Ok(2.to_string()));
+        assert_eq!(day.part_two(input), Ok(5.to_string()));
+    }
+
     #[test]
     fn test_part_two() {
         let day = DayEight::default();
@@ -189,7 +207,7 @@ pub mod test {
             34,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
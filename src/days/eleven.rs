@@ -1,64 +1,68 @@
 use crate::days::Day;
+use crate::error::{parse_token, Result};
+use crate::utils::big::BigUint;
+use crate::utils::checked::TryArithmetic;
+use crate::utils::math::{count_digits, split_digits};
 use std::collections::HashMap;
-use std::time::Instant;
 
 #[derive(Default)]
 pub struct DayEleven {}
 
 impl DayEleven {
-    fn parse_stones(input: &str) -> HashMap<u64, u64> {
-        input.split(" ").fold(HashMap::new(), |mut map, stone| {
-            map.entry(stone.parse().unwrap())
-                .and_modify(|count| *count += 1)
-                .or_insert(1);
-            map
-        })
+    /// The number of stones with each engraving. The counts can outgrow a `u64` after enough
+    /// blinks.
+    fn parse_stones(input: &str) -> Result<HashMap<u64, BigUint>> {
+        input
+            .split_whitespace()
+            .try_fold(HashMap::new(), |mut map, stone| {
+                *map.entry(parse_token(input, stone)?)
+                    .or_insert(BigUint::zero()) += &BigUint::from(1);
+                Ok(map)
+            })
     }
 
-    fn blink(stone_lists: &HashMap<u64, u64>) -> HashMap<u64, u64> {
+    fn blink(stone_lists: &HashMap<u64, BigUint>) -> Result<HashMap<u64, BigUint>> {
         stone_lists
             .iter()
-            .fold(HashMap::new(), |mut new_map, (stone, count)| {
-                let stone_string = stone.to_string();
-                let new_values = match (stone, stone_string.len()) {
-                    (0, _) => vec![1],
-                    (_, len) if len % 2 == 0 => {
-                        let (left, right) = stone_string.split_at(stone_string.len() / 2);
-                        vec![left.parse().unwrap(), right.parse().unwrap()]
+            .try_fold(HashMap::new(), |mut new_map, (stone, count)| {
+                let new_values = match (stone, count_digits(*stone)) {
+                    (0, _) => (1, None),
+                    (_, digits) if digits % 2 == 0 => {
+                        let (left, right) = split_digits(*stone, digits / 2);
+                        (left, Some(right))
                     }
-                    _ => vec![stone * 2024],
+                    _ => (stone.try_mul(2024)?, None),
                 };
 
                 // Count up the new values
-                new_values.iter().for_each(|value| {
-                    new_map
-                        .entry(*value)
-                        .and_modify(|new_count| *new_count += count)
-                        .or_insert(*count);
-                });
-                new_map
+                for value in [Some(new_values.0), new_values.1].into_iter().flatten() {
+                    *new_map.entry(value).or_insert(BigUint::zero()) += count;
+                }
+                Ok(new_map)
             })
     }
 
-    fn count_stones(stones: &HashMap<u64, u64>, blinks: u32) -> u64 {
-        let start = Instant::now();
-        (0..blinks)
-            .inspect(|blink| println!("Blink {blink}: {:?}", start.elapsed()))
-            .fold(stones.clone(), |next, _| DayEleven::blink(&next))
+    fn count_stones(stones: &HashMap<u64, BigUint>, blinks: u32) -> Result<BigUint> {
+        Ok((0..blinks)
+            .try_fold(stones.clone(), |next, _| DayEleven::blink(&next))?
             .values()
-            .sum()
+            .sum())
     }
 }
 
 impl Day for DayEleven {
-    fn part_one(&self, input: &str) -> String {
-        let stones = DayEleven::parse_stones(input);
-        DayEleven::count_stones(&stones, 25).to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let stones = DayEleven::parse_stones(input)?;
+        Ok(DayEleven::count_stones(&stones, 25)?.to_string())
+    }
+
+    fn part_two(&self, input: &str) -> Result<String> {
+        let stones = DayEleven::parse_stones(input)?;
+        Ok(DayEleven::count_stones(&stones, 75)?.to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let stones = DayEleven::parse_stones(input);
-        DayEleven::count_stones(&stones, 75).to_string()
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayEleven::parse_stones(input).map(|_| ()))
     }
 }
 
@@ -76,17 +80,29 @@ pub mod test {
             ("1036288 7 2 20 24 4048 1 4048 8096 28 67 60 32", 22),
         ];
         for (input, expected) in cases {
-            let stones = DayEleven::parse_stones(input);
-            assert_eq!(DayEleven::blink(&stones).values().sum::<u64>(), expected)
+            let stones = DayEleven::parse_stones(input).unwrap();
+            let counts = DayEleven::blink(&stones).unwrap();
+            assert_eq!(counts.values().sum::<BigUint>(), BigUint::from(expected))
         }
     }
 
+    #[test]
+    fn test_many_blinks() {
+        let stones = DayEleven::parse_stones("125 17").unwrap();
+        let count = DayEleven::count_stones(&stones, 500).unwrap();
+        assert_eq!(count.to_u64(), None);
+        assert_eq!(
+            count.to_string(),
+            "9332778333171329647192501576620127875703052322448004098317987815134809182249715590744339095"
+        );
+    }
+
     #[test]
     fn test_part_one() {
         let day = DayEleven::default();
         let cases = vec![("125 17", 55312)];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -95,7 +111,7 @@ pub mod test {
         let day = DayEleven::default();
         let cases = vec![("", 0)];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
@@ -1,32 +1,29 @@
 use crate::days::Day;
+use crate::error::{Error, Result};
+use crate::utils::parse::two_sections;
 use crate::utils::{Coord, Direction, Grid};
+use crate::viz::{Canvas, Cell, Flow, Frame, Visualize};
+use crossterm::style::Color;
 use std::fmt::{Debug, Formatter};
 
 #[derive(Eq, PartialEq, Hash, Clone, Copy)]
 enum Object {
     Robot,
     Box,
+    BoxLeft,
+    BoxRight,
     Wall,
     Empty,
 }
 
-impl Object {
-    fn new(letter: &char) -> Self {
-        match letter {
-            '@' => Self::Robot,
-            'O' => Self::Box,
-            '#' => Self::Wall,
-            _ => Self::Empty,
-        }
-    }
-}
-
 impl Debug for Object {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Object::Empty => write!(f, "."),
             Object::Wall => write!(f, "#"),
             Object::Box => write!(f, "O"),
+            Object::BoxLeft => write!(f, "["),
+            Object::BoxRight => write!(f, "]"),
             Object::Robot => write!(f, "@"),
         }
     }
@@ -36,120 +33,158 @@ impl Debug for Object {
 pub struct DayFifteen {}
 
 impl DayFifteen {
-    fn parse_factory(input: &str, width: u8) -> (Coord, Grid<Object>, Vec<Direction>) {
-        let (factory_input, moves_input) = input.split_once("\n\n").unwrap();
-
-        let factory =
-            factory_input
-                .lines()
-                .enumerate()
-                .fold(Grid::new(), |mut factory, (y, line)| {
-                    line.chars().enumerate().for_each(|(x, letter)| {
-                        let new_x = x * width as usize;
-                        factory.set(Coord::new(new_x as i64, y as i64), Object::new(&letter));
-                        for offset in 1..width {
-                            if letter == '@' {
-                                factory.set(
-                                    Coord::new((new_x + offset as usize) as i64, y as i64),
-                                    Object::Empty,
-                                );
-                            } else {
-                                factory.set(
-                                    Coord::new((new_x + offset as usize) as i64, y as i64),
-                                    Object::new(&letter),
-                                );
-                            }
-                        }
-                    });
-                    factory
-                });
-
-        let robot = {
-            factory
-                .iter()
-                .find(|(_, obj)| **obj == Object::Robot)
-                .unwrap()
-                .0
-        };
+    fn parse_factory(input: &str, width: usize) -> Result<(Coord, Grid<Object>, Vec<Direction>)> {
+        let (factory_input, moves_input) = two_sections(
+            input,
+            "expected a blank line between the warehouse and the moves",
+        )?;
+
+        // Everything but the robot is widened, with boxes split into two halves
+        let parsed = Grid::parse_widened(factory_input, "@", width, |letter, offset| {
+            match (letter, offset) {
+                ('@', 0) => Some(Object::Robot),
+                ('@', _) | ('.', _) => Some(Object::Empty),
+                ('#', _) => Some(Object::Wall),
+                ('O', _) if width == 1 => Some(Object::Box),
+                ('O', 0) => Some(Object::BoxLeft),
+                ('O', _) => Some(Object::BoxRight),
+                _ => None,
+            }
+        })?;
+        let robot = parsed
+            .find('@')
+            .ok_or_else(|| Error::Input("No robot found in the warehouse".to_string()))?;
 
         let moves = moves_input
             .lines()
-            .flat_map(|line| line.chars())
-            .flat_map(|letter| Direction::new(&letter))
-            .collect();
+            .flat_map(|line| {
+                line.char_indices()
+                    .map(move |(index, arrow)| (&line[index..], arrow))
+            })
+            .map(|(rest, arrow)| {
+                Direction::from_arrow(&arrow)
+                    .ok_or_else(|| Error::at(input, rest, format!("unexpected move '{}'", arrow)))
+            })
+            .collect::<Result<_>>()?;
 
-        (*robot, factory, moves)
+        Ok((robot, parsed.grid, moves))
     }
 
     fn score_factory(factory: &Grid<Object>) -> i64 {
         factory
             .iter()
-            .filter(|(_, object)| **object == Object::Box)
+            .filter(|(_, object)| matches!(object, Object::Box | Object::BoxLeft))
             .map(|(position, _)| position.x + position.y * 100)
             .sum()
     }
 
+    /// The cells to set when pushing whatever is at `position`, or `None` if it's blocked. Every
+    /// cell that moves is emptied first, so the moved objects can then overwrite each other.
     fn find_moves(
         position: &Coord,
-        factory: &mut Grid<Object>,
+        factory: &Grid<Object>,
         direction: &Direction,
     ) -> Option<Vec<(Coord, Object)>> {
-        let next = match direction {
-            Direction::Up => position.plus_y(-1),
-            Direction::Down => position.plus_y(1),
-            Direction::Left => position.plus_x(-1),
-            Direction::Right => position.plus_x(1),
-        };
-        let next_obj = factory.get(&next)?;
-
-        if *next_obj == Object::Wall {
-            return None;
-        }
+        let offset = direction.offset();
 
-        let current_obj = *factory.get(position)?;
-
-        if *next_obj == Object::Empty {
-            return Some(vec![(next, current_obj), (*position, Object::Empty)]);
+        let mut moving: Vec<Coord> = vec![];
+        let mut pending = vec![*position];
+        while let Some(current) = pending.pop() {
+            if moving.contains(&current) {
+                continue;
+            }
+            let next = current + offset;
+            match factory.get(&next)? {
+                Object::Wall => return None,
+                Object::Empty => (),
+                // Pushing half of a wide box up or down pushes the other half too
+                Object::BoxLeft if offset.y != 0 => pending.extend([next, next.plus_x(1)]),
+                Object::BoxRight if offset.y != 0 => pending.extend([next, next.plus_x(-1)]),
+                _ => pending.push(next),
+            }
+            moving.push(current);
         }
 
-        match Self::find_moves(&next, factory, direction) {
-            None => None,
-            Some(moves) => {
-                let mut total_moves = moves.clone();
-                total_moves.extend(vec![(next, current_obj), (*position, Object::Empty)]);
-                Some(total_moves)
+        let emptied = moving.iter().map(|coord| (*coord, Object::Empty));
+        let moved = moving
+            .iter()
+            .map(|coord| (*coord + offset, factory[*coord]));
+        Some(emptied.chain(moved).collect())
+    }
+
+    /// Moves the robot one step if it can, returning where it ends up.
+    fn move_robot(robot: &Coord, factory: &mut Grid<Object>, direction: &Direction) -> Coord {
+        let mut current = *robot;
+        if let Some(next) = Self::find_moves(robot, factory, direction) {
+            for (coord, obj) in next {
+                factory.set(coord, obj);
+                if obj == Object::Robot {
+                    current = coord;
+                }
             }
         }
+        current
     }
 
     fn execute_moves(robot: &Coord, factory: &mut Grid<Object>, moves: &[Direction]) -> i64 {
         let mut current = *robot;
         for direction in moves {
-            if let Some(next) = Self::find_moves(&current, factory, direction) {
-                for (coord, obj) in next {
-                    factory.set(coord, obj);
-                    if obj == Object::Robot {
-                        current = coord;
-                    }
-                }
-            }
+            current = Self::move_robot(&current, factory, direction);
         }
 
         DayFifteen::score_factory(factory)
     }
+
+    fn style(object: &Object) -> Cell {
+        match object {
+            Object::Robot => Cell::coloured('@', Color::Yellow),
+            Object::Box => Cell::coloured('O', Color::Cyan),
+            Object::BoxLeft => Cell::coloured('[', Color::Cyan),
+            Object::BoxRight => Cell::coloured(']', Color::Cyan),
+            Object::Wall => Cell::coloured('#', Color::DarkGrey),
+            Object::Empty => Cell::plain('.'),
+        }
+    }
+}
+
+impl Visualize for DayFifteen {
+    fn visualize(&self, input: &str, part: u8, canvas: &mut dyn Canvas) -> Result<Option<String>> {
+        let (mut robot, mut factory, moves) = DayFifteen::parse_factory(input, part as usize)?;
+        for (index, direction) in moves.iter().enumerate() {
+            robot = Self::move_robot(&robot, &mut factory, direction);
+            let frame = Frame::from_grid(&factory, Self::style)
+                .focus(robot)
+                .caption(format!(
+                    "Move {} of {}: {}",
+                    index + 1,
+                    moves.len(),
+                    direction
+                ));
+            if canvas.draw(&frame) == Flow::Stop {
+                return Ok(None);
+            }
+        }
+        Ok(Some(DayFifteen::score_factory(&factory).to_string()))
+    }
 }
 
 impl Day for DayFifteen {
-    fn part_one(&self, input: &str) -> String {
-        let (robot, mut factory, moves) = DayFifteen::parse_factory(input, 1);
-        println!("{factory:?}");
-        DayFifteen::execute_moves(&robot, &mut factory, &moves).to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let (robot, mut factory, moves) = DayFifteen::parse_factory(input, 1)?;
+        Ok(DayFifteen::execute_moves(&robot, &mut factory, &moves).to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let (_robot, factory, _moves) = DayFifteen::parse_factory(input, 2);
-        println!("{factory:?}");
-        input.to_string()
+    fn part_two(&self, input: &str) -> Result<String> {
+        let (robot, mut factory, moves) = DayFifteen::parse_factory(input, 2)?;
+        Ok(DayFifteen::execute_moves(&robot, &mut factory, &moves).to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayFifteen::parse_factory(input, 1).map(|_| ()))
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
     }
 }
 
@@ -200,7 +235,26 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#,
             ),
         ];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_unknown_moves() {
+        let day = DayFifteen::default();
+        let cases = vec![
+            ("#####\n#@.O#\n#####\n\n<>\n^vx<", (6, 3)),
+            ("#@.#\n\n<u", (3, 2)),
+        ];
+        for (input, (line, column)) in cases {
+            match day.part_one(input) {
+                Err(Error::Parse {
+                    line: error_line,
+                    column: error_column,
+                    ..
+                }) => assert_eq!((error_line, error_column), (line, column)),
+                other => panic!("Expected a parse error, got {:?}", other),
+            }
         }
     }
 
@@ -221,21 +275,32 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#,
                 618,
             ),
             (
-                r#"########
-#..O.O.#
-##@.O..#
-#...O..#
-#.#.O..#
-#...O..#
-#......#
-########
+                r#"##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
 
-<^^>>>vv<v>>v<<"#,
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#,
                 9021,
             ),
         ];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
@@ -1,32 +1,42 @@
 use crate::days::Day;
-use regex::Regex;
+use crate::error::Result;
+use crate::utils::parse::{exactly, separated, two_sections};
 use std::collections::{HashMap, HashSet};
 
+type PageOrder = HashMap<u32, HashSet<u32>>;
+
 #[derive(Default)]
 pub struct DayFive {}
 
 impl DayFive {
-    fn get_page_order(input: &str) -> HashMap<u32, HashSet<u32>> {
-        let re = Regex::new(r"(\d+)\|(\d+)");
-        re.unwrap()
-            .captures_iter(input)
-            .filter(|caps| caps.len() > 0)
-            .map(|caps| {
-                (
-                    caps[1].parse::<u32>().unwrap(),
-                    caps[2].parse::<u32>().unwrap(),
-                )
-            })
-            .fold(HashMap::new(), |mut map, (before, after)| {
-                let set = map.entry(after).or_default();
+    fn parse_manual(input: &str) -> Result<(PageOrder, Vec<Vec<u32>>)> {
+        let (instructions, updates) = two_sections(
+            input,
+            "expected a blank line between the page ordering rules and the updates",
+        )?;
+        let page_order = DayFive::get_page_order(input, instructions)?;
+        let updates = updates
+            .lines()
+            .map(|line| separated(input, line, ","))
+            .collect::<Result<_>>()?;
+        Ok((page_order, updates))
+    }
+
+    fn get_page_order(input: &str, instructions: &str) -> Result<PageOrder> {
+        instructions
+            .lines()
+            .try_fold(HashMap::new(), |mut map, line| {
+                let pages = separated(input, line, "|")?;
+                let [before, after] = exactly(input, line, pages, "expected '<page>|<page>'")?;
+                let set: &mut HashSet<u32> = map.entry(after).or_default();
                 set.insert(before);
-                map
+                Ok(map)
             })
     }
 
     fn add_page(
         page: u32,
-        page_order: &HashMap<u32, HashSet<u32>>,
+        page_order: &PageOrder,
         pages: &HashSet<u32>,
         visited_pages: &mut HashSet<u32>,
         out_of_order: &mut HashSet<u32>,
@@ -58,21 +68,17 @@ impl DayFive {
         is_good
     }
 
-    fn sum_middle_pages(page_order: HashMap<u32, HashSet<u32>>, updates: &str) -> (u32, u32) {
+    fn sum_middle_pages(page_order: PageOrder, updates: &[Vec<u32>]) -> (u32, u32) {
         let mut correct_sum = 0;
         let mut incorrect_correct_sum = 0;
-        for line in updates.lines() {
-            let pages: Vec<u32> = line
-                .split(",")
-                .map(|page| page.parse::<u32>().unwrap())
-                .collect();
+        for pages in updates {
             let pages_set: HashSet<u32> = pages.clone().into_iter().collect();
             let mut visited_pages: HashSet<u32> = HashSet::new();
             let mut is_good: bool = true;
             let mut out_of_order = HashSet::new();
             let mut new_order: Vec<u32> = Vec::new();
 
-            for page in &pages {
+            for page in pages {
                 if !Self::add_page(
                     *page,
                     &page_order,
@@ -95,16 +101,22 @@ impl DayFive {
 }
 
 impl Day for DayFive {
-    fn part_one(&self, input: &str) -> String {
-        let (instructions, pages) = input.split_once("\n\n").unwrap();
-        let page_order = DayFive::get_page_order(instructions);
-        DayFive::sum_middle_pages(page_order, pages).0.to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let (page_order, updates) = DayFive::parse_manual(input)?;
+        Ok(DayFive::sum_middle_pages(page_order, &updates)
+            .0
+            .to_string())
+    }
+
+    fn part_two(&self, input: &str) -> Result<String> {
+        let (page_order, updates) = DayFive::parse_manual(input)?;
+        Ok(DayFive::sum_middle_pages(page_order, &updates)
+            .1
+            .to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let (instructions, pages) = input.split_once("\n\n").unwrap();
-        let page_order = DayFive::get_page_order(instructions);
-        DayFive::sum_middle_pages(page_order, pages).1.to_string()
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayFive::parse_manual(input).map(|_| ()))
     }
 }
 
@@ -147,7 +159,7 @@ pub mod test {
             143,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -186,7 +198,7 @@ pub mod test {
             123,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
@@ -1,8 +1,13 @@
 use crate::days::Day;
-use crate::utils::{Coord, Diagonal};
-use regex::Regex;
+use crate::error::{Error, Result};
+use crate::utils::math::{crt, lcm};
+use crate::utils::parse::{exactly, integers};
+use crate::utils::search::dfs;
+use crate::utils::simulate::simulate;
+use crate::utils::{Coord, Diagonal, Grid};
+use crate::viz::{Canvas, Cell, Flow, Frame, Visualize};
+use crossterm::style::Color;
 use std::collections::{HashMap, HashSet};
-use std::iter::successors;
 
 #[derive(Debug, Clone, Hash)]
 struct Robot {
@@ -12,27 +17,17 @@ struct Robot {
 
 impl Robot {
     fn step(&self, height: i64, width: i64) -> Self {
-        let next_x = self.position.x + self.velocity.x;
-        let next_y = self.position.y + self.velocity.y;
-
-        let adjusted_x = match next_x {
-            next_x if next_x < 0 => width + next_x,
-            next_x if next_x >= width => next_x - width,
-            _ => next_x,
-        };
-
-        let adjusted_y = match next_y {
-            next_y if next_y < 0 => height + next_y,
-            next_y if next_y >= height => next_y - height,
-            _ => next_y,
-        };
-
         Robot {
-            position: Coord::new(adjusted_x, adjusted_y),
+            position: (self.position + self.velocity).wrap(width, height),
             velocity: self.velocity,
         }
     }
 
+    /// Where the robot will be after `seconds`.
+    fn at(&self, seconds: i64, height: i64, width: i64) -> Coord {
+        (self.position + self.velocity * seconds).wrap(width, height)
+    }
+
     fn quadrant(&self, height: i64, width: i64) -> Option<Diagonal> {
         let top_left = (0..width / 2, 0..height / 2);
         let top_right = (1 + width / 2..width, 0..height / 2);
@@ -70,116 +65,197 @@ impl Default for DayFourteen {
 }
 
 impl DayFourteen {
-    fn parse_robots(input: &str) -> Vec<Robot> {
-        let find_numbers = Regex::new(r"(\d+),(\d+).+?(-*\d+),(-*\d+)").unwrap();
+    fn parse_robots(input: &str) -> Result<Vec<Robot>> {
         input
             .lines()
             .map(|line| {
-                let cap = find_numbers.captures(line).unwrap();
-                Robot {
-                    position: Coord::new(cap[1].parse().unwrap(), cap[2].parse().unwrap()),
-                    velocity: Coord::new(cap[3].parse().unwrap(), cap[4].parse().unwrap()),
-                }
+                let numbers = integers(input, line)?;
+                let [x, y, dx, dy] =
+                    exactly(input, line, numbers, "expected 'p=<x>,<y> v=<x>,<y>'")?;
+                Ok(Robot {
+                    position: Coord::new(x, y),
+                    velocity: Coord::new(dx, dy),
+                })
             })
             .collect()
     }
 
+    fn step_all(robots: &[Robot], width: i64, height: i64) -> Vec<Robot> {
+        robots
+            .iter()
+            .map(|robot| robot.step(height, width))
+            .collect()
+    }
+
     fn wait(robots: &[Robot], seconds: u32, width: i64, height: i64) -> u32 {
-        successors(Some((robots.to_owned(), 1)), |(current_robots, second)| {
-            if *second > seconds {
+        let mut simulation = simulate(robots.to_vec(), |robots| {
+            Self::step_all(robots, width, height)
+        });
+        Self::safety_factor(simulation.run(seconds as u64), width, height)
+    }
+
+    /// The product of the number of robots in each quadrant.
+    fn safety_factor(robots: &[Robot], width: i64, height: i64) -> u32 {
+        robots
+            .iter()
+            .fold(HashMap::new(), |mut counter, robot| {
+                if let Some(quadrant) = robot.quadrant(height, width) {
+                    counter
+                        .entry(quadrant)
+                        .and_modify(|value| *value += 1)
+                        .or_insert(1);
+                }
+                counter
+            })
+            .values()
+            .product()
+    }
+
+    /// The size of the empty area around `point`, or `None` if it reaches the edge of the room.
+    fn is_shape(point: &Coord, robots: &HashSet<Coord>, height: i64, width: i64) -> Option<u32> {
+        let in_room =
+            |point: &Coord| (0..width).contains(&point.x) && (0..height).contains(&point.y);
+        let empty_neighbours = |point: &Coord| match in_room(point) {
+            true => point
+                .neighbours4()
+                .into_iter()
+                .filter(|next| !robots.contains(next))
+                .collect(),
+            false => vec![],
+        };
+        let mut area = 0;
+        for point in dfs(*point, empty_neighbours) {
+            if !in_room(&point) {
                 return None;
             }
-            let next_robots = current_robots
-                .iter()
-                .map(|robot| robot.step(height, width))
-                .collect::<Vec<Robot>>();
-            Some((next_robots, second + 1))
+            area += 1;
+        }
+        Some(area)
+    }
+
+    fn find_christmas_tree(robots: &[Robot], width: i64, height: i64) -> Option<u32> {
+        // Every robot is back where it started once both axes have wrapped round together
+        let period = lcm(width as u64, height as u64);
+        simulate(robots.to_vec(), |robots| {
+            Self::step_all(robots, width, height)
         })
-        .map(|(end_robots, _)| end_robots)
-        .last()
-        .unwrap()
-        .iter()
-        .fold(HashMap::new(), |mut counter, robot| {
-            if let Some(quadrant) = robot.quadrant(height, width) {
-                counter
-                    .entry(quadrant)
-                    .and_modify(|value| *value += 1)
-                    .or_insert(1);
-            }
-            counter
+        .run_until(period, |robots| {
+            let positions = robots.iter().map(|robot| robot.position).collect();
+            Self::is_tree(&positions, width, height)
         })
-        .values()
-        .product()
-    }
-
-    fn is_shape(
-        point: &Coord,
-        robots: &HashSet<Coord>,
-        height: i64,
-        width: i64,
-        visited: &mut HashSet<Coord>,
-    ) -> Option<u32> {
-        if point.x < 0 || point.x >= width || point.y < 0 || point.y >= height {
-            return None;
-        }
+        .map(|second| second as u32)
+    }
 
-        if robots.contains(point) || visited.contains(point) {
-            return Some(0);
-        }
+    /// Works out when the tree appears from when the robots bunch up along each axis, rather
+    /// than searching every second. The columns repeat every `width` seconds and the rows every
+    /// `height`, so the second that is tightest in both follows from the Chinese Remainder
+    /// Theorem. Returns `None` unless there's a tree at that second.
+    fn solve_christmas_tree(robots: &[Robot], width: i64, height: i64) -> Option<u32> {
+        let x = Self::tightest(robots, width, |coord| coord.x);
+        let y = Self::tightest(robots, height, |coord| coord.y);
+        let (second, _) = crt(&[(x as i128, width as i128), (y as i128, height as i128)])?;
+        let positions = robots
+            .iter()
+            .map(|robot| robot.at(second as i64, height, width))
+            .collect();
+        Self::is_tree(&positions, width, height).then_some(second as u32)
+    }
 
-        visited.insert(*point);
-
-        [
-            point.plus_x(1),
-            point.plus_x(-1),
-            point.plus_y(1),
-            point.plus_y(-1),
-        ]
-        .iter()
-        .map(|next| Self::is_shape(next, robots, height, width, visited))
-        .try_fold(1, |curr, next| next.map(|next| curr + next))
-    }
-
-    fn find_christmas_tree(robots: &[Robot], width: i64, height: i64) -> u32 {
-        successors(Some((robots.to_owned(), 0)), |(current_robots, second)| {
-            let next_robots = current_robots
-                .iter()
-                .map(|robot| robot.step(height, width))
-                .collect::<Vec<Robot>>();
-            Some((next_robots, second + 1))
-        })
-        .map(|(next_robots, second)| {
-            (
-                next_robots
+    /// The second, within one period of `size`, when the robots' positions along `axis` vary
+    /// the least.
+    fn tightest(robots: &[Robot], size: i64, axis: impl Fn(&Coord) -> i64) -> i64 {
+        (0..size)
+            .min_by_key(|second| {
+                let (sum, squares) = robots
                     .iter()
-                    .map(|robot| robot.position)
-                    .collect::<HashSet<Coord>>(),
-                second,
-            )
+                    .map(|robot| {
+                        (axis(&robot.position) + axis(&robot.velocity) * second).rem_euclid(size)
+                    })
+                    .fold((0, 0), |(sum, squares), value| {
+                        (sum + value, squares + value * value)
+                    });
+                // The variance, scaled up by the number of robots squared to stay an integer
+                robots.len() as i64 * squares - sum * sum
+            })
+            .unwrap_or(0)
+    }
+
+    /// Whether the robots enclose a large empty area in the middle of the room.
+    fn is_tree(robots: &HashSet<Coord>, width: i64, height: i64) -> bool {
+        let mid = Coord::new(width / 2, height / 2);
+        if robots.contains(&mid) {
+            return false;
+        }
+        let area = Self::is_shape(&mid, robots, height, width);
+        area.is_some_and(|area| area > 50)
+    }
+
+    fn frame(robots: &[Robot], width: i64, height: i64) -> Frame {
+        let mut room = Grid::filled(width as usize, height as usize, false);
+        for robot in robots {
+            room.set(robot.position, true);
+        }
+        Frame::from_grid(&room, |robot| match robot {
+            true => Cell::coloured('#', Color::Green),
+            false => Cell::plain('.'),
         })
-        .find(|(next_robots, _)| {
-            let mid = Coord::new(width / 2, height / 2);
-            if next_robots.contains(&mid) {
-                return false;
+    }
+}
+
+impl Visualize for DayFourteen {
+    fn visualize(&self, input: &str, part: u8, canvas: &mut dyn Canvas) -> Result<Option<String>> {
+        let (width, height) = (self.width, self.height);
+        let mut robots = DayFourteen::parse_robots(input)?;
+        let seconds = match part {
+            1 => 100,
+            _ => width * height,
+        };
+        for second in 0..=seconds {
+            let frame = Self::frame(&robots, width, height).caption(format!("Second {}", second));
+            if canvas.draw(&frame) == Flow::Stop {
+                return Ok(None);
             }
-            let area = Self::is_shape(&mid, next_robots, height, width, &mut HashSet::new());
-            area.is_some_and(|area| area > 50)
-        })
-        .unwrap()
-        .1
+            let positions = robots.iter().map(|robot| robot.position).collect();
+            if part == 2 && Self::is_tree(&positions, width, height) {
+                return Ok(Some(second.to_string()));
+            }
+            if part == 1 && second == seconds {
+                return Ok(Some(
+                    Self::safety_factor(&robots, width, height).to_string(),
+                ));
+            }
+            robots = Self::step_all(&robots, width, height);
+        }
+        Err(Error::Input(
+            "The robots never form a Christmas tree".to_string(),
+        ))
     }
 }
 
 impl Day for DayFourteen {
-    fn part_one(&self, input: &str) -> String {
-        let robots = DayFourteen::parse_robots(input);
-        DayFourteen::wait(&robots, 100, self.width, self.height).to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let robots = DayFourteen::parse_robots(input)?;
+        Ok(DayFourteen::wait(&robots, 100, self.width, self.height).to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let robots = DayFourteen::parse_robots(input);
+    fn part_two(&self, input: &str) -> Result<String> {
+        let robots = DayFourteen::parse_robots(input)?;
+        if robots.is_empty() {
+            return Err(Error::Input("No robots found".to_string()));
+        }
+
+        DayFourteen::solve_christmas_tree(&robots, self.width, self.height)
+            .or_else(|| DayFourteen::find_christmas_tree(&robots, self.width, self.height))
+            .map(|second| second.to_string())
+            .ok_or_else(|| Error::Input("The robots never form a Christmas tree".to_string()))
+    }
 
-        DayFourteen::find_christmas_tree(&robots, self.width, self.height).to_string()
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayFourteen::parse_robots(input).map(|_| ()))
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
     }
 }
 
@@ -212,16 +288,40 @@ p=9,5 v=-3,-3"#,
             ),
         ];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
+    /// Robots that form the outline of a box around the middle of the room after `seconds`.
+    fn box_at(seconds: i64) -> String {
+        let outline = (45..=55)
+            .flat_map(|x| [(x, 46), (x, 56)])
+            .chain((47..56).flat_map(|y| [(45, y), (55, y)]));
+        outline
+            .enumerate()
+            .map(|(index, (x, y))| {
+                let velocity = Coord::new(index as i64 % 5 + 1, index as i64 % 3 - 4);
+                let start = (Coord::new(x, y) - velocity * seconds).wrap(101, 103);
+                format!(
+                    "p={},{} v={},{}\n",
+                    start.x, start.y, velocity.x, velocity.y
+                )
+            })
+            .collect()
+    }
+
     #[test]
     fn test_part_two() {
         let day = DayFourteen::default();
-        let cases = vec![("", 0)];
+        let cases = vec![(box_at(0), 0), (box_at(42), 42)];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(&input), Ok(expected.to_string()))
         }
+        assert!(day.part_two("p=0,0 v=1,1").is_err());
+        let robots = DayFourteen::parse_robots(&box_at(42)).unwrap();
+        assert_eq!(
+            DayFourteen::solve_christmas_tree(&robots, 101, 103),
+            Some(42)
+        );
     }
 }
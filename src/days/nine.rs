@@ -1,23 +1,29 @@
 use crate::days::Day;
+use crate::error::Result;
+use crate::utils::checked::TryArithmetic;
+use crate::utils::parse::digits;
 use std::ops::Range;
 
 #[derive(Default)]
 pub struct DayNine {}
 
 impl DayNine {
-    fn load_diskmap(fs: &str) -> (u32, Vec<Option<u32>>) {
-        let (files, spaces) = fs
-            .chars()
-            .map(|c| c.to_string().parse::<u32>().unwrap())
-            .enumerate()
-            .fold((vec![], vec![]), |(mut files, mut space), (i, num)| {
+    fn parse_digits(input: &str) -> Result<Vec<u32>> {
+        digits(input, input.trim_end())
+    }
+
+    fn load_diskmap(fs: &[u32]) -> (u32, Vec<Option<u32>>) {
+        let (files, spaces) = fs.iter().copied().enumerate().fold(
+            (vec![], vec![]),
+            |(mut files, mut space), (i, num)| {
                 match i % 2 {
                     0 => files.push(num),
                     1 => space.push(num),
                     _ => (),
                 }
                 (files, space)
-            });
+            },
+        );
         let mut total_file_size: u32 = 0;
         let mut out = files
             .iter()
@@ -46,39 +52,35 @@ impl DayNine {
         (total_file_size, out)
     }
 
-    fn load_files_and_slice_locations(fs: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
-        let (file_slices, space_slices, _) = fs
-            .chars()
-            .map(|c| c.to_string().parse::<u32>().unwrap())
-            .enumerate()
-            .fold(
-                (Vec::new(), Vec::new(), 0),
-                |(mut files, mut spaces, start), (i, num)| {
-                    let end = start + num as usize;
-                    if end > start {
-                        match i % 2 {
-                            0 => files.push(start..end),
-                            1 => spaces.push(start..end),
-                            _ => (),
-                        }
+    fn load_files_and_slice_locations(fs: &[u32]) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+        let (file_slices, space_slices, _) = fs.iter().copied().enumerate().fold(
+            (Vec::new(), Vec::new(), 0),
+            |(mut files, mut spaces, start), (i, num)| {
+                let end = start + num as usize;
+                if end > start {
+                    match i % 2 {
+                        0 => files.push(start..end),
+                        1 => spaces.push(start..end),
+                        _ => (),
                     }
-                    (files, spaces, end)
-                },
-            );
+                }
+                (files, spaces, end)
+            },
+        );
         (file_slices, space_slices)
     }
 
-    fn defrag(total_file_size: &u32, disk_map: &[Option<u32>]) -> u64 {
+    fn defrag(total_file_size: &u32, disk_map: &[Option<u32>]) -> Result<u64> {
         let mut next_file = disk_map.iter().rev().flatten();
 
         disk_map[..(*total_file_size as usize)]
             .iter()
             .enumerate()
             .map(|(i, pointer)| match pointer {
-                Some(p) => i as u64 * *p as u64,
-                None => i as u64 * *next_file.next().unwrap() as u64,
+                Some(p) => (i as u64).try_mul(*p as u64),
+                None => (i as u64).try_mul(*next_file.next().unwrap() as u64),
             })
-            .sum()
+            .try_fold(0, |sum, product| sum.try_add(product?))
     }
 
     fn contiguous_defrag(
@@ -109,22 +111,28 @@ impl DayNine {
 }
 
 impl Day for DayNine {
-    fn part_one(&self, input: &str) -> String {
-        let (total_file_size, disk_map) = DayNine::load_diskmap(input);
-        DayNine::defrag(&total_file_size, &disk_map).to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let digits = DayNine::parse_digits(input)?;
+        let (total_file_size, disk_map) = DayNine::load_diskmap(&digits);
+        Ok(DayNine::defrag(&total_file_size, &disk_map)?.to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let (_, ref mut disk_map) = DayNine::load_diskmap(input);
-        let (files, spaces) = DayNine::load_files_and_slice_locations(input);
+    fn part_two(&self, input: &str) -> Result<String> {
+        let digits = DayNine::parse_digits(input)?;
+        let (_, ref mut disk_map) = DayNine::load_diskmap(&digits);
+        let (files, spaces) = DayNine::load_files_and_slice_locations(&digits);
         DayNine::contiguous_defrag(&files, spaces, disk_map);
-        disk_map
+        let checksum = disk_map
             .iter()
             .enumerate()
             .filter(|(_, number)| number.is_some())
-            .map(|(i, number)| i as u64 * number.unwrap() as u64)
-            .sum::<u64>()
-            .to_string()
+            .map(|(i, number)| (i as u64).try_mul(number.unwrap() as u64))
+            .try_fold(0, |sum, product| sum.try_add(product?))?;
+        Ok(checksum.to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayNine::parse_digits(input).map(|_| ()))
     }
 }
 
@@ -137,7 +145,7 @@ pub mod test {
         let day = DayNine::default();
         let cases = vec![("2333133121414131402", 1928)];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -146,7 +154,7 @@ pub mod test {
         let day = DayNine::default();
         let cases = vec![("2333133121414131402", 2858)];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
@@ -1,37 +1,41 @@
 use crate::days::Day;
+use crate::error::Result;
+use crate::utils::checked::TryArithmetic;
+use crate::utils::parse::{exactly, words};
 use std::collections::HashMap;
 
 #[derive(Default)]
 pub struct DayOne {}
 impl DayOne {
-    fn number_lists(input: &str) -> (Vec<i32>, Vec<i32>) {
+    fn number_lists(input: &str) -> Result<(Vec<i32>, Vec<i32>)> {
         let lines: Vec<&str> = input.lines().collect();
         let mut left: Vec<i32> = Vec::with_capacity(lines.len());
         let mut right: Vec<i32> = Vec::with_capacity(lines.len());
         for line in lines {
-            let numbers: Vec<&str> = line.split_whitespace().collect();
-            left.push(numbers[0].parse().expect(""));
-            right.push(numbers[1].parse().expect(""));
+            let [left_item, right_item] =
+                exactly(input, line, words(input, line)?, "expected two numbers")?;
+            left.push(left_item);
+            right.push(right_item);
         }
-        (left, right)
+        Ok((left, right))
     }
 }
 
 impl Day for DayOne {
-    fn part_one(&self, input: &str) -> String {
-        let (mut left, mut right) = DayOne::number_lists(input);
+    fn part_one(&self, input: &str) -> Result<String> {
+        let (mut left, mut right) = DayOne::number_lists(input)?;
         left.sort();
         right.sort();
-        let mut sum = 0;
-        for (left_item, right_item) in left.into_iter().zip(right.into_iter()) {
-            sum += (left_item - right_item).abs()
+        let mut sum = 0i32;
+        for (left_item, right_item) in left.into_iter().zip(right) {
+            sum = sum.try_add(left_item.try_sub(right_item)?.abs())?;
         }
-        sum.to_string()
+        Ok(sum.to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let (left, right) = DayOne::number_lists(input);
-        let mut similarity_score = 0;
+    fn part_two(&self, input: &str) -> Result<String> {
+        let (left, right) = DayOne::number_lists(input)?;
+        let mut similarity_score = 0i32;
         let mut right_count: HashMap<i32, i32> = HashMap::new();
 
         for right_item in right {
@@ -39,8 +43,51 @@ impl Day for DayOne {
         }
         for left_item in left {
             let count = *right_count.get(&left_item).get_or_insert(&0);
-            similarity_score += left_item * count;
+            similarity_score = similarity_score.try_add(left_item.try_mul(*count)?)?;
+        }
+        Ok(similarity_score.to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayOne::number_lists(input).map(|_| ()))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_part_one() {
+        let day = DayOne::default();
+        let cases = vec![("3   4\n4   3\n2   5\n1   3\n3   9\n3   3", 11)];
+        for (input, expected) in cases {
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_part_two() {
+        let day = DayOne::default();
+        let cases = vec![("3   4\n4   3\n2   5\n1   3\n3   9\n3   3", 31)];
+        for (input, expected) in cases {
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = vec![("3   4\n\n", (2, 1)), ("3   4\n4   x3", (2, 5))];
+        for (input, (line, column)) in cases {
+            match DayOne::number_lists(input) {
+                Err(Error::Parse {
+                    line: error_line,
+                    column: error_column,
+                    ..
+                }) => assert_eq!((error_line, error_column), (line, column)),
+                other => panic!("Expected a parse error, got {:?}", other),
+            }
         }
-        similarity_score.to_string()
     }
 }
@@ -1,20 +1,16 @@
 use crate::days::Day;
+use crate::error::Result;
+use crate::utils::checked::{concat, TrySum};
+use crate::utils::parse::key_values;
 
 #[derive(Default)]
 pub struct DaySeven {}
 
 impl DaySeven {
-    fn parse_input(input: &str) -> Vec<(u64, Vec<u64>)> {
+    fn parse_input(input: &str) -> Result<Vec<(u64, Vec<u64>)>> {
         input
             .lines()
-            .map(|line| {
-                let (result, numbers_strings) = line.split_once(":").unwrap();
-                let numbers: Vec<u64> = numbers_strings
-                    .split_whitespace()
-                    .map(|number| number.parse().unwrap())
-                    .collect();
-                (result.parse::<u64>().unwrap(), numbers)
-            })
+            .map(|line| key_values(input, line, ":"))
             .collect()
     }
 
@@ -34,10 +30,14 @@ impl DaySeven {
             .iter()
             .map(|operator| {
                 let next_progress = match operator {
-                    '*' => progress * numbers[0],
-                    '+' => progress + numbers[0],
-                    '|' => format!("{progress}{}", numbers[0]).parse().unwrap(),
-                    _ => 0,
+                    '*' => progress.checked_mul(numbers[0]),
+                    '+' => progress.checked_add(numbers[0]),
+                    '|' => concat(*progress, numbers[0]),
+                    _ => None,
+                };
+                // Anything too big for a u64 is bigger than the result
+                let Some(next_progress) = next_progress else {
+                    return 0;
                 };
                 Self::find_solvable_sum(operators, result, &numbers[1..], &next_progress)
             })
@@ -47,9 +47,9 @@ impl DaySeven {
 }
 
 impl Day for DaySeven {
-    fn part_one(&self, input: &str) -> String {
-        let problems = DaySeven::parse_input(input);
-        problems
+    fn part_one(&self, input: &str) -> Result<String> {
+        let problems = DaySeven::parse_input(input)?;
+        let sum = problems
             .iter()
             .map(|(result, numbers)| {
                 (
@@ -63,14 +63,14 @@ impl Day for DaySeven {
                 )
             })
             .filter(|(_, answer)| *answer > 0)
-            .map(|(result, _)| result)
-            .sum::<u64>()
-            .to_string()
+            .map(|(result, _)| *result)
+            .try_sum()?;
+        Ok(sum.to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let problems = DaySeven::parse_input(input);
-        problems
+    fn part_two(&self, input: &str) -> Result<String> {
+        let problems = DaySeven::parse_input(input)?;
+        let sum = problems
             .iter()
             .map(|(result, numbers)| {
                 (
@@ -84,9 +84,13 @@ impl Day for DaySeven {
                 )
             })
             .filter(|(_, answer)| *answer > 0)
-            .map(|(result, _)| result)
-            .sum::<u64>()
-            .to_string()
+            .map(|(result, _)| *result)
+            .try_sum()?;
+        Ok(sum.to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DaySeven::parse_input(input).map(|_| ()))
     }
 }
 
@@ -110,7 +114,7 @@ pub mod test {
             3749,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -130,7 +134,7 @@ pub mod test {
             11387,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
@@ -1,104 +1,39 @@
 use crate::days::Day;
-use std::collections::{HashMap, HashSet};
-use std::fmt::{Display, Formatter};
-use std::thread;
-
-#[derive(Eq, Hash, PartialEq, Clone)]
-struct Coord {
-    x: i32,
-    y: i32,
-}
-
-impl Coord {
-    fn new(coords: (i32, i32)) -> Coord {
-        Coord {
-            x: coords.0,
-            y: coords.1,
-        }
-    }
-
-    fn step(&self, direction: char) -> Coord {
-        match direction {
-            '^' => Coord::new((self.x, self.y - 1)),
-            '>' => Coord::new((self.x + 1, self.y)),
-            'v' => Coord::new((self.x, self.y + 1)),
-            '<' => Coord::new((self.x - 1, self.y)),
-            _ => Coord::new((self.x, self.y)),
-        }
-    }
-}
-
-impl Display for Coord {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
+use crate::error::{Error, Result};
+use crate::utils::parallel::par_count;
+use crate::utils::simulate::simulate;
+use crate::utils::{self, Coord, Direction};
+use crate::viz::{Canvas, Cell, Flow, Frame, Visualize};
+use crossterm::style::Color;
+use std::collections::HashSet;
 
 #[derive(Clone)]
 struct Grid {
-    grid: HashMap<(i32, i32), char>,
-    width: u32,
-    height: u32,
+    grid: utils::Grid<char>,
     starting_pos: Coord,
+    starting_direction: Direction,
 }
 
 impl Grid {
-    fn new(input: &str) -> Grid {
-        let mut grid = HashMap::new();
-        let mut starting_pos: Option<(i32, i32)> = None;
-        for (y, line) in input.lines().enumerate() {
-            for (x, value) in line.chars().enumerate() {
-                match value {
-                    '^' | '>' | 'v' | '<' => starting_pos = Some((x as i32, y as i32)),
-                    _ => (),
-                }
-                grid.insert((x as i32, y as i32), value);
-            }
-        }
-        let height = input.lines().collect::<Vec<_>>().len() as u32;
-        let width: u32 = input.lines().collect::<Vec<_>>()[0].len() as u32;
-        Grid {
-            grid,
-            height,
-            width,
-            starting_pos: Coord::new(starting_pos.unwrap()),
-        }
-    }
-
-    fn get(&self, x: i32, y: i32) -> Option<&char> {
-        self.grid.get(&(x, y))
+    fn new(input: &str) -> Result<Grid> {
+        let parsed = utils::Grid::parse(input, "^>v<", Some)?;
+        let (guard, starting_pos) = *parsed
+            .markers
+            .first()
+            .ok_or_else(|| Error::Input("No guard found on the map".to_string()))?;
+        Ok(Grid {
+            starting_pos,
+            starting_direction: Direction::new(&guard).unwrap_or(Direction::Up),
+            grid: parsed.grid,
+        })
     }
 
     fn set(&mut self, coord: &Coord, letter: char) {
-        self.grid.insert((coord.x, coord.y), letter);
+        self.grid.set(*coord, letter);
     }
 
     fn get_pos(&self, coord: &Coord) -> Option<&char> {
-        self.get(coord.x, coord.y)
-    }
-
-    #[allow(dead_code)]
-    fn print(&self) -> String {
-        (0..self.width)
-            .map(|y| {
-                let line = (0..self.height)
-                    .map(|x| self.get(x as i32, y as i32).unwrap_or(&' '))
-                    .collect::<String>();
-                line + "\n"
-            })
-            .collect()
-    }
-
-    #[allow(dead_code)]
-    fn print_around(&self, x: i32, y: i32, window_length: i32) -> String {
-        (-window_length..=window_length)
-            .map(|y_offset| {
-                let line = (-window_length..=window_length)
-                    .map(|x_offset| self.get(x + x_offset, y + y_offset).unwrap_or(&' '))
-                    .collect::<String>();
-                line + "\n"
-            })
-            .collect()
+        self.grid.get(coord)
     }
 }
 
@@ -106,85 +41,136 @@ impl Grid {
 pub struct DaySix {}
 
 impl DaySix {
+    /// The guard's next position and heading, or `None` once they've left the map.
+    fn patrol(grid: &Grid, guard: &Option<(Coord, Direction)>) -> Option<(Coord, Direction)> {
+        let (position, direction) = (*guard)?;
+        match grid.get_pos(&(position + direction))? {
+            '#' | '0' => Some((position, direction.turn_right())),
+            _ => Some((position + direction, direction)),
+        }
+    }
+
+    /// The number of places the guard visits, or `None` if they're stuck in a loop.
     fn count_guard_steps(grid: &Grid) -> Option<u32> {
-        let mut guard_pos = grid.starting_pos.clone();
-        let mut positions: HashSet<(Coord, char)> = HashSet::new();
-        let directions = ['^', '>', 'v', '<'];
-        let mut direction = directions
-            .iter()
-            .position(|dir| dir == grid.get_pos(&grid.starting_pos).unwrap())?;
-        let mut next_pos = guard_pos.step(directions[direction]);
-        while let Some(place) = grid.get_pos(&next_pos) {
-            match place {
-                '#' | '0' => direction = (direction + 1) % directions.len(),
-                _ if positions.contains(&(guard_pos.clone(), directions[direction])) => {
-                    return None;
-                }
-                _ => {
-                    positions.insert((guard_pos.clone(), directions[direction]));
-                    guard_pos = next_pos.clone()
-                }
-            }
-            next_pos = guard_pos.step(directions[direction])
+        let start = Some((grid.starting_pos, grid.starting_direction));
+        let patrol = |guard: &Option<(Coord, Direction)>| Self::patrol(grid, guard);
+        // A guard who leaves stays gone, so every patrol ends up repeating
+        let mut simulation = simulate(start, patrol);
+        simulation.brent();
+        if simulation.state().is_some() {
+            return None;
         }
-        positions.insert((guard_pos, directions[direction]));
-        Some(
-            positions
-                .iter()
-                .map(|pos| pos.0.clone())
-                .collect::<HashSet<Coord>>()
-                .len() as u32,
-        )
+        let visited: HashSet<Coord> = simulate(start, patrol)
+            .map_while(|guard| guard)
+            .map(|(position, _)| position)
+            .collect();
+        Some(visited.len() as u32)
     }
 
     fn find_obstruction_count(grid: &Grid) -> u32 {
-        let mut guard_pos = grid.starting_pos.clone();
-        let directions = ['^', '>', 'v', '<'];
-        let mut direction = directions
-            .iter()
-            .position(|dir| dir == grid.get_pos(&grid.starting_pos).unwrap())
-            .unwrap();
-        let mut next_pos = guard_pos.step(directions[direction]);
-        let mut handles = vec![];
+        let mut guard_pos = grid.starting_pos;
+        let mut direction = grid.starting_direction;
+        let mut next_pos = guard_pos + direction;
+        let mut candidates = HashSet::new();
         while let Some(place) = grid.get_pos(&next_pos) {
-            if place != &'#' {
-                let mut new_grid = grid.clone();
-                new_grid.set(&next_pos, '0');
-                let new_obs_pos = next_pos.clone();
-                handles.push(thread::spawn(move || {
-                    match Self::count_guard_steps(&new_grid) {
-                        Some(_) => None,
-                        None => Some(new_obs_pos),
-                    }
-                }));
+            // The guard would notice an obstruction placed where they're standing
+            if place != &'#' && next_pos != grid.starting_pos {
+                candidates.insert(next_pos);
             }
 
             match place {
-                '#' => direction = (direction + 1) % directions.len(),
-                _ => guard_pos = next_pos.clone(),
+                '#' => direction = direction.turn_right(),
+                _ => guard_pos = next_pos,
             }
-            next_pos = guard_pos.step(directions[direction])
+            next_pos = guard_pos + direction
         }
 
-        handles
-            .into_iter()
-            .map(|handle| handle.join().unwrap())
-            .filter(|coord| !coord.is_none())
-            .map(|coord| coord.unwrap())
-            .collect::<HashSet<Coord>>()
-            .len() as u32
+        let candidates: Vec<Coord> = candidates.into_iter().collect();
+        par_count(&candidates, |candidate| {
+            let mut new_grid = grid.clone();
+            new_grid.set(candidate, '0');
+            Self::count_guard_steps(&new_grid).is_none()
+        }) as u32
+    }
+}
+
+impl Visualize for DaySix {
+    /// Walks the guard around the map, marking each obstruction that would trap them in a loop
+    /// for part two.
+    fn visualize(&self, input: &str, part: u8, canvas: &mut dyn Canvas) -> Result<Option<String>> {
+        let grid = Grid::new(input)?;
+        let mut visited = grid.grid.clone();
+        let mut obstructions = HashSet::new();
+        let (mut guard_pos, mut direction) = (grid.starting_pos, grid.starting_direction);
+        loop {
+            visited.set(guard_pos, 'X');
+            let mut frame = Frame::from_grid(&visited, |place| match place {
+                '#' => Cell::coloured('#', Color::DarkGrey),
+                'X' => Cell::coloured('X', Color::Blue),
+                _ => Cell::plain('.'),
+            });
+            for obstruction in &obstructions {
+                frame
+                    .cells
+                    .set(*obstruction, Cell::coloured('O', Color::Red));
+            }
+            frame.cells.set(
+                guard_pos,
+                Cell::coloured(
+                    direction.to_string().chars().next().unwrap_or('^'),
+                    Color::Yellow,
+                ),
+            );
+            let count = match part {
+                1 => visited.iter().filter(|(_, place)| **place == 'X').count(),
+                _ => obstructions.len(),
+            };
+            let frame = frame
+                .focus(guard_pos)
+                .caption(format!("Part {}: {}", part, count));
+            if canvas.draw(&frame) == Flow::Stop {
+                return Ok(None);
+            }
+
+            let next_pos = guard_pos + direction;
+            match visited.get(&next_pos) {
+                None => return Ok(Some(count.to_string())),
+                Some('#') => direction = direction.turn_right(),
+                Some(place) => {
+                    // An obstruction on the path so far would have been hit earlier
+                    if part == 2 && *place != 'X' && !obstructions.contains(&next_pos) {
+                        let mut obstructed = grid.clone();
+                        obstructed.set(&next_pos, '0');
+                        if Self::count_guard_steps(&obstructed).is_none() {
+                            obstructions.insert(next_pos);
+                        }
+                    }
+                    guard_pos = next_pos;
+                }
+            }
+        }
     }
 }
 
 impl Day for DaySix {
-    fn part_one(&self, input: &str) -> String {
-        let grid = Grid::new(input);
-        DaySix::count_guard_steps(&grid).unwrap().to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let grid = Grid::new(input)?;
+        DaySix::count_guard_steps(&grid)
+            .map(|steps| steps.to_string())
+            .ok_or_else(|| Error::Input("The guard never leaves the map".to_string()))
+    }
+
+    fn part_two(&self, input: &str) -> Result<String> {
+        let grid = Grid::new(input)?;
+        Ok(DaySix::find_obstruction_count(&grid).to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(Grid::new(input).map(|_| ()))
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let grid = Grid::new(input);
-        DaySix::find_obstruction_count(&grid).to_string()
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
     }
 }
 
@@ -209,7 +195,7 @@ pub mod test {
             41,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -230,7 +216,7 @@ pub mod test {
             6,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::days::Day;
+use crate::error::Result;
 
 #[derive(Default)]
 pub struct DayXXXX {}
@@ -6,12 +7,12 @@ pub struct DayXXXX {}
 impl DayXXXX {}
 
 impl Day for DayXXXX {
-    fn part_one(&self, input: &str) -> String {
-        input.to_string()
+    fn part_one(&self, _input: &str) -> Result<String> {
+        Ok(0.to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        input.to_string()
+    fn part_two(&self, _input: &str) -> Result<String> {
+        Ok(0.to_string())
     }
 }
 
@@ -24,7 +25,7 @@ pub mod test {
         let day = DayXXXX::default();
         let cases = vec![("", 0)];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -33,7 +34,7 @@ pub mod test {
         let day = DayXXXX::default();
         let cases = vec![("", 0)];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
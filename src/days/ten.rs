@@ -1,106 +1,54 @@
 use crate::days::Day;
-use std::collections::{HashMap, HashSet};
-
-#[derive(Eq, Hash, PartialEq, Clone)]
-struct Coord {
-    x: i32,
-    y: i32,
-}
-
-impl Coord {
-    fn plus_x(&self, offset: i32) -> Coord {
-        Coord {
-            x: self.x + offset,
-            y: self.y,
-        }
-    }
-
-    fn plus_y(&self, offset: i32) -> Coord {
-        Coord {
-            x: self.x,
-            y: self.y + offset,
-        }
-    }
-}
+use crate::error::Result;
+use crate::utils::parse::digit_grid;
+use crate::utils::search::{bfs, count_paths, grid_neighbours};
+use crate::utils::{self, Coord};
 
 #[derive(Clone)]
 struct Grid {
-    grid: HashMap<(i32, i32), i32>,
+    grid: utils::Grid<u32>,
     trailheads: Vec<Coord>,
 }
 
 impl Grid {
-    fn new(input: &str) -> Grid {
-        let mut grid = HashMap::new();
-        let mut trailheads: Vec<Coord> = vec![];
-        for (y, line) in input.lines().enumerate() {
-            for (x, value) in line.chars().enumerate() {
-                if value == '0' {
-                    trailheads.push(Coord {
-                        x: x as i32,
-                        y: y as i32,
-                    });
-                }
-                grid.insert((x as i32, y as i32), value.to_string().parse().unwrap());
-            }
-        }
-        Grid { grid, trailheads }
+    fn new(input: &str) -> Result<Grid> {
+        let parsed = digit_grid(input, "0")?;
+        let trailheads = parsed.find_all('0').collect();
+        Ok(Grid {
+            grid: parsed.grid,
+            trailheads,
+        })
     }
 
-    fn get(&self, coord: &Coord) -> Option<&i32> {
-        self.grid.get(&(coord.x, coord.y))
+    fn get(&self, coord: &Coord) -> Option<&u32> {
+        self.grid.get(coord)
     }
 }
+
 #[derive(Default)]
 pub struct DayTen {}
 
 impl DayTen {
-    fn count_hiking_trails(pos: &Coord, map: &Grid) -> HashSet<Coord> {
-        let elevation = match map.get(pos) {
-            Some(elevation) => elevation,
-            None => return HashSet::new(),
-        };
-
-        if *elevation == 9 {
-            return HashSet::from([pos.clone()]);
-        }
-        [pos.plus_x(1), pos.plus_x(-1), pos.plus_y(1), pos.plus_y(-1)]
-            .iter()
-            .filter(|next_pos| {
-                map.get(next_pos)
-                    .is_some_and(|value| *value == *elevation + 1)
-            })
-            .map(|next_pos| DayTen::count_hiking_trails(next_pos, map))
-            .fold(HashSet::new(), |mut acc, next| {
-                acc.extend(next);
-                acc
-            })
+    fn uphill(from: &u32, to: &u32) -> bool {
+        *to == from + 1
     }
 
-    fn count_hiking_trail_rating(pos: &Coord, map: &Grid) -> u32 {
-        let elevation = match map.get(pos) {
-            Some(elevation) => elevation,
-            None => return 0,
-        };
-
-        if *elevation == 9 {
-            return 1;
-        }
+    fn count_hiking_trails(pos: &Coord, map: &Grid) -> u32 {
+        bfs([*pos], grid_neighbours(&map.grid, Self::uphill))
+            .filter(|(next_pos, _)| map.get(next_pos) == Some(&9))
+            .count() as u32
+    }
 
-        [pos.plus_x(1), pos.plus_x(-1), pos.plus_y(1), pos.plus_y(-1)]
-            .iter()
-            .filter(|next_pos| {
-                map.get(next_pos)
-                    .is_some_and(|value| *value == *elevation + 1)
-            })
-            .map(|next_pos| DayTen::count_hiking_trail_rating(next_pos, map))
-            .sum()
+    fn count_hiking_trail_rating(pos: &Coord, map: &Grid) -> u32 {
+        count_paths(*pos, grid_neighbours(&map.grid, Self::uphill), |next_pos| {
+            map.get(next_pos) == Some(&9)
+        }) as u32
     }
 
     fn count_good_trailheads(map: &Grid) -> u32 {
         map.trailheads
             .iter()
-            .map(|trailhead| Self::count_hiking_trails(trailhead, map).len() as u32)
+            .map(|trailhead| Self::count_hiking_trails(trailhead, map))
             .sum()
     }
 
@@ -113,16 +61,20 @@ impl DayTen {
 }
 
 impl Day for DayTen {
-    fn part_one(&self, input: &str) -> String {
-        let map = Grid::new(input);
+    fn part_one(&self, input: &str) -> Result<String> {
+        let map = Grid::new(input)?;
 
-        DayTen::count_good_trailheads(&map).to_string()
+        Ok(DayTen::count_good_trailheads(&map).to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let map = Grid::new(input);
+    fn part_two(&self, input: &str) -> Result<String> {
+        let map = Grid::new(input)?;
+
+        Ok(DayTen::count_good_trailheads_rating(&map).to_string())
+    }
 
-        DayTen::count_good_trailheads_rating(&map).to_string()
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(Grid::new(input).map(|_| ()))
     }
 }
 
@@ -154,7 +106,7 @@ pub mod test {
             ),
         ];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -182,7 +134,7 @@ pub mod test {
             ),
         ];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
@@ -1,9 +1,8 @@
 use crate::days::Day;
+use crate::error::Result;
+use crate::utils::linalg::{cramer, diophantine, solve, Rational, Solution};
+use crate::utils::parse::{exactly, integers, sections};
 use crate::utils::Coord;
-use itertools::Itertools;
-use regex::Regex;
-use std::cmp::min;
-use std::collections::HashMap;
 
 #[derive(Debug)]
 struct Button {
@@ -18,132 +17,114 @@ struct PrizeMachine {
     b: Button,
 }
 
+impl PrizeMachine {
+    fn cost(&self, (a_presses, b_presses): (i128, i128)) -> i128 {
+        a_presses * self.a.cost as i128 + b_presses * self.b.cost as i128
+    }
+}
+
 #[derive(Default)]
 pub struct DayThirteen {}
 
 impl DayThirteen {
-    fn parse_machines(input: &str, prize_location_offset: i64) -> Vec<PrizeMachine> {
+    fn parse_machines(input: &str, prize_location_offset: i64) -> Result<Vec<PrizeMachine>> {
         /*
         Button A: X+94, Y+34
         Button B: X+22, Y+67
         Prize: X=8400, Y=5400
          */
 
-        let find_numbers = Regex::new(r"(\d+).+?(\d+)").unwrap();
-
-        input
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| {
-                let caps = find_numbers.captures(line).unwrap();
-                Coord {
-                    x: caps[1].parse().unwrap(),
-                    y: caps[2].parse().unwrap(),
-                }
-            })
-            .chunks(3)
+        sections(input)
             .into_iter()
-            .map(|chunk| {
-                let chunks: Vec<Coord> = chunk.collect();
-                PrizeMachine {
+            .map(|section| {
+                let numbers = integers(input, section)?;
+                let [a_x, a_y, b_x, b_y, prize_x, prize_y] =
+                    exactly(input, section, numbers, "expected two buttons and a prize")?;
+                Ok(PrizeMachine {
                     a: Button {
                         cost: 3,
-                        moves: chunks[0],
+                        moves: Coord::new(a_x, a_y),
                     },
                     b: Button {
                         cost: 1,
-                        moves: chunks[1],
+                        moves: Coord::new(b_x, b_y),
                     },
-                    prize_location: chunks[2].plus(prize_location_offset, prize_location_offset),
-                }
+                    prize_location: Coord::new(prize_x, prize_y)
+                        .plus(prize_location_offset, prize_location_offset),
+                })
             })
             .collect()
     }
-    fn run(
-        prize_machine: &PrizeMachine,
-        location: &Coord,
-        a_presses: u64,
-        b_presses: u64,
-        memo: &mut HashMap<Coord, Option<u64>>,
-    ) -> Option<u64> {
-        if let Some(cost) = memo.get(location) {
-            return *cost;
-        }
-
-        if a_presses > 100 && b_presses > 100
-            || location.x > prize_machine.prize_location.x
-            || location.y > prize_machine.prize_location.y
-        {
-            return None;
-        }
-
-        if prize_machine.prize_location == *location {
-            return Some(0);
-        }
-
-        let next_a = location.plus(prize_machine.a.moves.x, prize_machine.a.moves.y);
-        let a_cost = Self::run(prize_machine, &next_a, a_presses + 1, b_presses, memo);
-        let next_b = location.plus(prize_machine.b.moves.x, prize_machine.b.moves.y);
-        let b_cost = Self::run(prize_machine, &next_b, a_presses, b_presses + 1, memo);
 
-        let cost = match (a_cost, b_cost) {
-            (Some(a), None) => Some(a + prize_machine.a.cost),
-            (None, Some(b)) => Some(b + prize_machine.b.cost),
-            (Some(a), Some(b)) => Some(min(a + prize_machine.a.cost, b + prize_machine.b.cost)),
-            _ => None,
+    /// The fewest tokens that win the prize, or `None` if no number of presses reaches it.
+    fn calculate_cost(prize_machine: &PrizeMachine) -> Option<u64> {
+        let (a, b, prize) = (
+            prize_machine.a.moves,
+            prize_machine.b.moves,
+            prize_machine.prize_location,
+        );
+        let system = vec![
+            vec![Rational::from(a.x), Rational::from(b.x)],
+            vec![Rational::from(a.y), Rational::from(b.y)],
+        ];
+        let target = vec![Rational::from(prize.x), Rational::from(prize.y)];
+
+        let presses = match cramer(&system, &target) {
+            Some(presses) => {
+                let count = |index: usize| presses[index].to_integer().filter(|count| *count >= 0);
+                (count(0)?, count(1)?)
+            }
+            // The buttons move along the same line, so the prize has to be on it too
+            None if solve(&system, &target) == Solution::None => return None,
+            None => Self::cheapest_along_line(prize_machine)?,
         };
-
-        memo.insert(*location, cost);
-        cost
+        u64::try_from(prize_machine.cost(presses)).ok()
     }
-    fn get_prizes(machines: &[PrizeMachine]) -> u64 {
-        machines
-            .iter()
-            .filter_map(|machine| Self::run(machine, &Coord::new(0, 0), 0, 0, &mut HashMap::new()))
-            .sum()
-    }
-
-    fn calculate_cost(prize_machine: &PrizeMachine) -> Option<u64> {
-        let x1 = prize_machine.a.moves.x;
-        let x2 = prize_machine.b.moves.x;
-        let z1 = prize_machine.prize_location.x;
-
-        let y1 = prize_machine.a.moves.y;
-        let y2 = prize_machine.b.moves.y;
-        let z2 = prize_machine.prize_location.y;
 
-        let b_denom = -y2 * x1 + x2 * y1;
-
-        if b_denom == 0 || x1 == 0 {
-            return None;
-        }
-
-        let b = (y1 * z1 - z2 * x1) / b_denom;
-        let a = (z1 - b * x2) / x1;
-
-        let destination = Coord::new(a * x1 + b * x2, a * y1 + b * y2);
-
-        if destination != prize_machine.prize_location {
-            return None;
+    /// The cheapest presses for a machine whose buttons move in the same direction, from
+    /// whichever axis they move along.
+    fn cheapest_along_line(prize_machine: &PrizeMachine) -> Option<(i128, i128)> {
+        let (a, b, prize) = (
+            prize_machine.a.moves,
+            prize_machine.b.moves,
+            prize_machine.prize_location,
+        );
+        let (a, b, prize) = match (a.x, b.x) {
+            (0, 0) => (a.y as i128, b.y as i128, prize.y as i128),
+            _ => (a.x as i128, b.x as i128, prize.x as i128),
+        };
+        if a == 0 && b == 0 {
+            return (prize == 0).then_some((0, 0));
         }
 
-        Some((a * 3 + b) as u64)
+        // The cost changes steadily with k, so the cheapest is at one end of the allowed range
+        let solutions = diophantine(a, b, prize)?;
+        let (low, high) = solutions.non_negative()?;
+        [low, high]
+            .into_iter()
+            .filter(|k| *k != i128::MIN && *k != i128::MAX)
+            .map(|k| solutions.at(k))
+            .min_by_key(|presses| prize_machine.cost(*presses))
     }
 
-    fn get_prizes_fast(prize_machines: &[PrizeMachine]) -> u64 {
+    fn get_prizes(prize_machines: &[PrizeMachine]) -> u64 {
         prize_machines.iter().flat_map(Self::calculate_cost).sum()
     }
 }
 
 impl Day for DayThirteen {
-    fn part_one(&self, input: &str) -> String {
-        let machines = DayThirteen::parse_machines(input, 0);
-        DayThirteen::get_prizes(&machines).to_string()
+    fn part_one(&self, input: &str) -> Result<String> {
+        let machines = DayThirteen::parse_machines(input, 0)?;
+        Ok(DayThirteen::get_prizes(&machines).to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let machines = DayThirteen::parse_machines(input, 10000000000000);
-        DayThirteen::get_prizes_fast(&machines).to_string()
+    fn part_two(&self, input: &str) -> Result<String> {
+        let machines = DayThirteen::parse_machines(input, 10000000000000)?;
+        Ok(DayThirteen::get_prizes(&machines).to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayThirteen::parse_machines(input, 0).map(|_| ()))
     }
 }
 
@@ -173,7 +154,7 @@ Prize: X=18641, Y=10279"#,
             480,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -199,7 +180,32 @@ Prize: X=18641, Y=10279"#,
             875318608908u64,
         )];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_collinear_buttons() {
+        let day = DayThirteen::default();
+        let cases = vec![
+            (
+                "Button A: X+2, Y+2\nButton B: X+1, Y+1\nPrize: X=10, Y=10",
+                10,
+            ),
+            (
+                "Button A: X+4, Y+4\nButton B: X+1, Y+1\nPrize: X=10, Y=10",
+                8,
+            ),
+            ("Button A: X+2, Y+2\nButton B: X+4, Y+4\nPrize: X=5, Y=5", 0),
+            ("Button A: X+1, Y+1\nButton B: X+2, Y+2\nPrize: X=3, Y=4", 0),
+            (
+                "Button A: X+3, Y+0\nButton B: X+6, Y+0\nPrize: X=12, Y=0",
+                2,
+            ),
+            ("Button A: X+1, Y+3\nButton B: X+3, Y+1\nPrize: X=1, Y=1", 0),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(day.part_one(input), Ok(expected.to_string()), "{}", input)
         }
     }
 }
@@ -1,190 +1,81 @@
 use crate::days::Day;
-use crate::utils::{Coord, Diagonal, Grid};
-use std::collections::{HashMap, HashSet};
-
-#[derive(Eq, Hash, PartialEq, Clone, Copy, Default, Debug)]
-struct Region {
-    area: u32,
-    perimeter: u32,
-    corners: u32,
-}
-
-impl Region {
-    fn new(area: u32, perimeter: u32, corners: u32) -> Region {
-        Region {
-            area,
-            perimeter,
-            corners,
-        }
-    }
-
-    fn cost(&self) -> u32 {
-        self.area * self.perimeter
-    }
-
-    fn side_cost(&self) -> u32 {
-        self.area * self.corners
-    }
-
-    fn plus(&self, other: &Region) -> Region {
-        Region {
-            area: self.area + other.area,
-            perimeter: self.perimeter + other.perimeter,
-            corners: self.corners + other.corners,
-        }
-    }
-}
+use crate::error::Result;
+use crate::utils::region::{find_regions, Regions};
+use crate::utils::Grid;
+use crate::viz::{label_colour, Canvas, Cell, Frame, Visualize};
 
 #[derive(Default)]
 pub struct DayTwelve {}
 
 impl DayTwelve {
-    fn parse_farm(input: &str) -> Grid<char> {
-        input
-            .lines()
-            .enumerate()
-            .fold(Grid::new(), |mut grid, (y, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(|(x, plant)| {
-                        (
-                            Coord {
-                                x: x as i64,
-                                y: y as i64,
-                            },
-                            plant,
-                        )
-                    })
-                    .for_each(|(coord, plant)| grid.set(coord, plant));
-                grid
-            })
+    fn parse_farm(input: &str) -> Result<Grid<char>> {
+        Grid::parse(input, "", Some).map(|parsed| parsed.grid)
     }
 
-    fn is_interior_corner(
-        plant: &char,
-        coord: &Coord,
-        diagonal: &Diagonal,
-        grid: &Grid<char>,
-        neighbor_plant: Option<&char>,
-    ) -> bool {
-        let (one, two) = match diagonal {
-            Diagonal::TL => (
-                grid.get(&coord.plus_x(-1)).unwrap_or(&'+'),
-                grid.get(&coord.plus_y(1)).unwrap_or(&'+'),
-            ),
-            Diagonal::TR => (
-                grid.get(&coord.plus_x(1)).unwrap_or(&'+'),
-                grid.get(&coord.plus_y(1)).unwrap_or(&'+'),
-            ),
-            Diagonal::BR => (
-                grid.get(&coord.plus_x(1)).unwrap_or(&'+'),
-                grid.get(&coord.plus_y(-1)).unwrap_or(&'+'),
-            ),
-            Diagonal::BL => (
-                grid.get(&coord.plus_x(-1)).unwrap_or(&'+'),
-                grid.get(&coord.plus_y(-1)).unwrap_or(&'+'),
-            ),
-        };
-        one != plant
-            && neighbor_plant.is_none_or(|neighbor| neighbor == one)
-            && two != plant
-            && neighbor_plant.is_none_or(|neighbor| neighbor == two)
+    fn find_plots(farm: &Grid<char>) -> Regions {
+        find_regions(farm, |plant, other| plant == other)
     }
 
-    fn is_anterior_corner(
-        plant: &char,
-        coord: &Coord,
-        diagonal: &Diagonal,
-        grid: &Grid<char>,
-    ) -> bool {
-        let (next, opposite) = match diagonal {
-            Diagonal::TL => (coord.plus(-1, 1), Diagonal::BR),
-            Diagonal::TR => (coord.plus(1, 1), Diagonal::BL),
-            Diagonal::BR => (coord.plus(1, -1), Diagonal::TL),
-            Diagonal::BL => (coord.plus(-1, -1), Diagonal::TR),
-        };
-        let next_plant = grid.get(&next);
-        next_plant.is_none_or(|other| other != plant)
-            && Self::is_interior_corner(
-                next_plant.unwrap_or(&'+'),
-                &next,
-                &opposite,
-                grid,
-                Some(plant),
+    /// The total fencing price with each region priced by its perimeter, and by its sides.
+    fn calculate_fence_amount(plots: &Regions) -> (usize, usize) {
+        plots
+            .regions
+            .iter()
+            .map(|region| {
+                (
+                    region.area() * region.perimeter,
+                    region.area() * region.sides,
+                )
+            })
+            .fold(
+                (0, 0),
+                |(acc_cost, acc_side_cost), (next_cost, next_side_cost)| {
+                    (acc_cost + next_cost, acc_side_cost + next_side_cost)
+                },
             )
     }
+}
 
-    fn visit(
-        farm: &Grid<char>,
-        plant: &char,
-        coord: &Coord,
-        visited: &mut HashSet<Coord>,
-    ) -> Region {
-        let region = match (farm.get(coord), visited.contains(coord)) {
-            (Some(neighbor), false) if *neighbor == *plant => Region::new(1, 0, 0),
-            (Some(neighbor), _) if *neighbor != *plant => {
-                return Region::new(0, 1, 0);
-            }
-            (None, _) => {
-                return Region::new(0, 1, 0);
-            }
-            _ => return Region::default(),
-        };
-        visited.insert(*coord);
-
-        let corners = [Diagonal::TR, Diagonal::BL, Diagonal::BR, Diagonal::TL]
-            .iter()
-            .filter(|diagonal| {
-                Self::is_interior_corner(plant, coord, diagonal, farm, None)
-                    || Self::is_anterior_corner(plant, coord, diagonal, farm)
-            })
-            .count() as u32;
+impl Visualize for DayTwelve {
+    fn visualize(&self, input: &str, part: u8, canvas: &mut dyn Canvas) -> Result<Option<String>> {
+        let farm = DayTwelve::parse_farm(input)?;
+        let plots = DayTwelve::find_plots(&farm);
+        let cells = plots
+            .labels
+            .map(|coord, label| Cell::coloured(farm[coord], label_colour(*label)));
+        let frame = Frame::from_grid(&cells, |cell| *cell)
+            .caption(format!("{} regions", plots.regions.len()));
+        canvas.draw(&frame);
+        let (price, discounted) = DayTwelve::calculate_fence_amount(&plots);
+        Ok(Some(if part == 1 { price } else { discounted }.to_string()))
+    }
+}
 
-        region.plus(&Region::new(0, 0, corners)).plus(
-            &([
-                coord.plus_x(1),
-                coord.plus_x(-1),
-                coord.plus_y(1),
-                coord.plus_y(-1),
-            ]
-            .iter()
-            .map(|next| Self::visit(farm, plant, next, visited))
-            .reduce(|current, next| current.plus(&next))
-            .unwrap()),
+impl Day for DayTwelve {
+    fn part_one(&self, input: &str) -> Result<String> {
+        let farm = DayTwelve::parse_farm(input)?;
+        Ok(
+            DayTwelve::calculate_fence_amount(&DayTwelve::find_plots(&farm))
+                .0
+                .to_string(),
         )
     }
 
-    fn calculate_fence_amount(farm: &Grid<char>) -> (u32, u32) {
-        // let mut visited_regions: HashMap<char, HashSet<Coord>> = HashMap::new();
-        let mut visited: HashSet<Coord> = HashSet::new();
-        farm.iter()
-            .fold(HashMap::new(), |mut regions, (coord, plant)| {
-                let region = DayTwelve::visit(farm, plant, coord, &mut visited);
-                if region.cost() > 0 {
-                    let plant_regions: &mut Vec<Region> = regions.entry(plant).or_default();
-                    plant_regions.push(region);
-                }
-                regions
-            })
-            .values()
-            .flatten()
-            .map(|region| (region.cost(), region.side_cost()))
-            .reduce(|(acc_cost, acc_side_cost), (next_cost, next_side_cost)| {
-                (acc_cost + next_cost, acc_side_cost + next_side_cost)
-            })
-            .unwrap()
+    fn part_two(&self, input: &str) -> Result<String> {
+        let farm = DayTwelve::parse_farm(input)?;
+        Ok(
+            DayTwelve::calculate_fence_amount(&DayTwelve::find_plots(&farm))
+                .1
+                .to_string(),
+        )
     }
-}
 
-impl Day for DayTwelve {
-    fn part_one(&self, input: &str) -> String {
-        let farm = DayTwelve::parse_farm(input);
-        DayTwelve::calculate_fence_amount(&farm).0.to_string()
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayTwelve::parse_farm(input).map(|_| ()))
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let farm = DayTwelve::parse_farm(input);
-        DayTwelve::calculate_fence_amount(&farm).1.to_string()
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
     }
 }
 
@@ -223,11 +114,11 @@ VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
 MMMISSJEEE"#,
-                1206,
+                1930,
             ),
         ];
         for (input, expected) in cases {
-            assert_eq!(day.part_one(input), expected.to_string())
+            assert_eq!(day.part_one(input), Ok(expected.to_string()))
         }
     }
 
@@ -279,11 +170,11 @@ VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
 MMMISSJEEE"#,
-                1930,
+                1206,
             ),
         ];
         for (input, expected) in cases {
-            assert_eq!(day.part_two(input), expected.to_string())
+            assert_eq!(day.part_two(input), Ok(expected.to_string()))
         }
     }
 }
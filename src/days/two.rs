@@ -1,23 +1,14 @@
 use crate::days::Day;
+use crate::error::Result;
+use crate::utils::parse::words;
 use std::cmp::min;
 
 #[derive(Default)]
 pub struct DayTwo {}
 
 impl DayTwo {
-    fn parse_reports(input: &str) -> Vec<Vec<i32>> {
-        let mut reports: Vec<Vec<i32>> = Vec::new();
-        for line in input.lines() {
-            let report: Vec<i32> = line
-                .split_whitespace()
-                .map(|num| {
-                    num.parse()
-                        .unwrap_or_else(|_| panic!("Found a non-number {}", num))
-                })
-                .collect();
-            reports.push(report)
-        }
-        reports
+    fn parse_reports(input: &str) -> Result<Vec<Vec<i32>>> {
+        input.lines().map(|line| words(input, line)).collect()
     }
 
     fn check_report(&self, report: &[i32]) -> bool {
@@ -62,25 +53,29 @@ impl DayTwo {
 }
 
 impl Day for DayTwo {
-    fn part_one(&self, input: &str) -> String {
-        let reports = DayTwo::parse_reports(input);
+    fn part_one(&self, input: &str) -> Result<String> {
+        let reports = DayTwo::parse_reports(input)?;
         let count = reports
             .iter()
             .map(|report| self.is_report_safe(report, &false))
             .filter(|is_safe| *is_safe)
             .count();
 
-        count.to_string()
+        Ok(count.to_string())
     }
 
-    fn part_two(&self, input: &str) -> String {
-        let reports = DayTwo::parse_reports(input);
+    fn part_two(&self, input: &str) -> Result<String> {
+        let reports = DayTwo::parse_reports(input)?;
         let count = reports
             .iter()
             .map(|report| self.is_report_safe(report, &true))
             .filter(|is_safe| *is_safe)
             .count();
-        count.to_string()
+        Ok(count.to_string())
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<()>> {
+        Some(DayTwo::parse_reports(input).map(|_| ()))
     }
 }
 
@@ -0,0 +1,137 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Part of the input could not be parsed, with a 1-based line and column.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input as a whole doesn't describe a puzzle that can be solved.
+    Input(String),
+    /// A calculation overflowed, caught by the `checked` feature.
+    #[cfg_attr(not(feature = "checked"), allow(dead_code))]
+    Overflow(String),
+    /// An error raised while solving one part of a day.
+    Part {
+        day: u8,
+        part: u8,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Builds a parse error positioned at `token`, which must be a slice of `input`.
+    pub fn at(input: &str, token: &str, message: impl Display) -> Error {
+        let start = input.as_ptr() as usize;
+        let position = token.as_ptr() as usize;
+        debug_assert!(
+            (start..=start + input.len()).contains(&position),
+            "the token {:?} isn't part of the input",
+            token
+        );
+        Error::at_offset(input, position.saturating_sub(start), message)
+    }
+
+    /// Builds a parse error positioned `offset` bytes into `input`. Offsets past the end or
+    /// inside a character are moved back to the start of that character.
+    pub fn at_offset(input: &str, offset: usize, message: impl Display) -> Error {
+        let before = &input[..input.floor_char_boundary(offset)];
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        Error::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn in_part(self, day: u8, part: u8) -> Error {
+        Error::Part {
+            day,
+            part,
+            source: Box::new(self),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            Error::Input(message) => write!(f, "{}", message),
+            Error::Overflow(expression) => write!(f, "arithmetic overflow in {}", expression),
+            Error::Part { day, part, source } => {
+                write!(f, "Day {}, part {}: {}", day, part, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Parses `token`, a slice of `input`, reporting failures at the token's position.
+pub fn parse_token<T>(input: &str, token: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token
+        .parse()
+        .map_err(|err| Error::at(input, token, format!("couldn't parse '{}': {}", token, err)))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "1 2\n3 x\n";
+        let token = &input[6..7];
+        assert_eq!(
+            parse_token::<u32>(input, token),
+            Err(Error::Parse {
+                line: 2,
+                column: 3,
+                message: "couldn't parse 'x': invalid digit found in string".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        let input = "ab\ncé";
+        let cases = vec![
+            (0, (1, 1)),
+            (3, (2, 1)),
+            (4, (2, 2)),
+            (5, (2, 2)),
+            (6, (2, 3)),
+            (99, (2, 3)),
+        ];
+        for (offset, expected) in cases {
+            match Error::at_offset(input, offset, "here") {
+                Error::Parse { line, column, .. } => assert_eq!((line, column), expected),
+                other => panic!("Expected a parse error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let input = "ab\ncd";
+        let error = Error::at(input, &input[3..3], "expected a number").in_part(3, 2);
+        assert_eq!(
+            error.to_string(),
+            "Day 3, part 2: line 2, column 1: expected a number"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::answers::{Answers, Verdict};
+use crate::days::Day;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A puzzle example, `example_N.txt`, with the answers from `example_N.answers.txt`.
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    pub answers: Answers,
+}
+
+/// Every example in a day's input directory, ordered by number.
+pub fn find_examples(day_dir: &Path) -> io::Result<Vec<Example>> {
+    let entries = match fs::read_dir(day_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+    let mut numbers = vec![];
+    for entry in entries {
+        let file_name = entry?.file_name();
+        let number = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix("example_"))
+            .and_then(|name| name.strip_suffix(".txt"))
+            .and_then(|number| number.parse::<u8>().ok());
+        numbers.extend(number);
+    }
+    numbers.sort();
+
+    numbers
+        .into_iter()
+        .map(|number| {
+            let name = format!("example_{}", number);
+            Ok(Example {
+                input: fs::read_to_string(day_dir.join(format!("{}.txt", name)))?,
+                answers: Answers::load(&day_dir.join(Answers::example_name(number)))?,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Runs each of `parts` that has a known answer for the example, describing each failure.
+pub fn check_example(number: u8, day: &dyn Day, example: &Example, parts: &[u8]) -> Vec<String> {
+    parts
+        .iter()
+        .copied()
+        .filter(|part| example.answers.get(*part).is_some())
+        .filter_map(|part| {
+            let answer = match part {
+                1 => day.part_one(&example.input),
+                _ => day.part_two(&example.input),
+            };
+            let failure = match answer {
+                Ok(answer) => match example.answers.check(part, &answer) {
+                    Verdict::Fail { expected } => {
+                        format!("expected {}, got {}", expected, answer)
+                    }
+                    _ => return None,
+                },
+                Err(err) => err.to_string(),
+            };
+            Some(format!(
+                "Day {}, part {}, {}: {}",
+                number, part, example.name, failure
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::days::registered_days;
+
+    #[test]
+    fn test_examples() {
+        let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        let failures: Vec<String> = registered_days()
+            .flat_map(|registration| {
+                let day = registration.day();
+                let day_dir = input_dir.join(format!("day_{}", registration.number));
+                find_examples(&day_dir)
+                    .unwrap()
+                    .iter()
+                    .flat_map(|example| {
+                        check_example(registration.number, day.as_ref(), example, &[1, 2])
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,58 @@
+mod fetch;
+
+use crate::config::Config;
+use crate::input::fetch::{CurlFetcher, Provider};
+use std::io::Read;
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+/// Where a day's puzzle input is read from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    /// `input.txt` in the day's directory, fetched and cached there if it's missing
+    Puzzle,
+    /// An `example_N.txt` in the day's directory
+    Example(u8),
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    pub fn read(&self, day: &u8) -> io::Result<String> {
+        match self {
+            Source::Puzzle => Provider {
+                cache_dir: input_dir(),
+                fetcher: CurlFetcher::from_env(),
+            }
+            .get(*day),
+            Source::Example(number) => {
+                read_file(day_dir(day).join(format!("example_{}.txt", number)))
+            }
+            Source::Path(path) if path.as_os_str() == "-" => Source::Stdin.read(day),
+            Source::Path(path) => read_file(path.clone()),
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+fn read_file(path: PathBuf) -> io::Result<String> {
+    fs::read_to_string(&path)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))
+}
+
+/// The directory holding every `day_N` directory: `AOC_INPUT_DIR` if set, then the config's
+/// `input_dir`, then `inputs` in the working directory.
+pub fn input_dir() -> PathBuf {
+    env::var_os("AOC_INPUT_DIR")
+        .map(PathBuf::from)
+        .or_else(|| Config::global().input_dir.clone())
+        .unwrap_or(PathBuf::from("inputs"))
+}
+
+pub fn day_dir(day: &u8) -> PathBuf {
+    input_dir().join(format!("day_{}", day))
+}
@@ -0,0 +1,203 @@
+use crate::config::Config;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::{env, fs, io};
+
+const YEAR: u16 = 2024;
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "aoc24 (github.com/tfrat/aoc-2024)";
+
+/// Downloads a day's puzzle input.
+pub trait Fetcher {
+    fn fetch(&self, day: u8) -> io::Result<String>;
+}
+
+/// Fetches inputs over HTTP with the `curl` command, authenticating with a session cookie.
+pub struct CurlFetcher {
+    pub base_url: String,
+    pub session: Option<String>,
+}
+
+impl CurlFetcher {
+    /// A fetcher using `AOC_SESSION` and `AOC_BASE_URL`, falling back to the config's
+    /// `session` and `base_url`, then to adventofcode.com.
+    pub fn from_env() -> CurlFetcher {
+        let config = Config::global();
+        CurlFetcher {
+            base_url: env::var("AOC_BASE_URL")
+                .ok()
+                .or(config.base_url.clone())
+                .unwrap_or(DEFAULT_BASE_URL.to_string()),
+            session: env::var("AOC_SESSION").ok().or(config.session.clone()),
+        }
+    }
+
+    fn url(&self, day: u8) -> String {
+        format!(
+            "{}/{}/day/{}/input",
+            self.base_url.trim_end_matches('/'),
+            YEAR,
+            day
+        )
+    }
+}
+
+impl Fetcher for CurlFetcher {
+    fn fetch(&self, day: u8) -> io::Result<String> {
+        let session = self.session.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no cached input and AOC_SESSION is not set to fetch it",
+            )
+        })?;
+        // The cookie goes in a config read from stdin, so the session isn't on the command line
+        // where other users could see it
+        let mut child = Command::new("curl")
+            .args([
+                "--fail",
+                "--silent",
+                "--show-error",
+                "--user-agent",
+                USER_AGENT,
+                "--config",
+                "-",
+            ])
+            .arg(self.url(day))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(cookie_config(session).as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "fetching {} failed: {}",
+                self.url(day),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        String::from_utf8(output.stdout).map_err(io::Error::other)
+    }
+}
+
+/// A curl config line sending the session cookie, quoted as curl expects.
+fn cookie_config(session: &str) -> String {
+    let quoted = session
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("cookie = \"session={}\"\n", quoted)
+}
+
+/// Serves puzzle inputs from `<cache_dir>/day_N/input.txt`, fetching and caching any that
+/// are missing.
+pub struct Provider<F: Fetcher> {
+    pub cache_dir: PathBuf,
+    pub fetcher: F,
+}
+
+impl<F: Fetcher> Provider<F> {
+    pub fn cache_path(&self, day: u8) -> PathBuf {
+        self.cache_dir
+            .join(format!("day_{}", day))
+            .join("input.txt")
+    }
+
+    pub fn get(&self, day: u8) -> io::Result<String> {
+        let path = self.cache_path(day);
+        match fs::read_to_string(&path) {
+            Ok(input) => return Ok(input),
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            Err(_) => (),
+        }
+        let input = self
+            .fetcher
+            .fetch(day)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+        write_cache(&path, &input)?;
+        Ok(input)
+    }
+}
+
+fn write_cache(path: &Path, input: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, input)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use std::cell::Cell;
+
+    struct FakeFetcher {
+        calls: Cell<u32>,
+    }
+
+    impl Fetcher for FakeFetcher {
+        fn fetch(&self, day: u8) -> io::Result<String> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(format!("input for day {}\n", day))
+        }
+    }
+
+    fn provider(name: &str) -> Provider<FakeFetcher> {
+        let cache_dir = env::temp_dir().join(format!("aoc24-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+        Provider {
+            cache_dir,
+            fetcher: FakeFetcher {
+                calls: Cell::new(0),
+            },
+        }
+    }
+
+    #[test]
+    fn test_fetches_once_then_caches() {
+        let provider = provider("fetch");
+        assert_eq!(provider.get(3).unwrap(), "input for day 3\n");
+        assert_eq!(provider.get(3).unwrap(), "input for day 3\n");
+        assert_eq!(provider.fetcher.calls.get(), 1);
+        assert_eq!(
+            fs::read_to_string(provider.cache_path(3)).unwrap(),
+            "input for day 3\n"
+        );
+        fs::remove_dir_all(&provider.cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_never_fetches_cached_input() {
+        let provider = provider("cached");
+        write_cache(&provider.cache_path(5), "cached").unwrap();
+        assert_eq!(provider.get(5).unwrap(), "cached");
+        assert_eq!(provider.fetcher.calls.get(), 0);
+        fs::remove_dir_all(&provider.cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_url() {
+        let fetcher = CurlFetcher {
+            base_url: "http://localhost:8080/".to_string(),
+            session: None,
+        };
+        assert_eq!(fetcher.url(7), "http://localhost:8080/2024/day/7/input");
+        assert!(fetcher.fetch(7).is_err());
+    }
+
+    #[test]
+    fn test_cookie_config() {
+        let cases = vec![
+            ("abc123", "cookie = \"session=abc123\"\n"),
+            ("a\"b\\c", "cookie = \"session=a\\\"b\\\\c\"\n"),
+            ("a\nurl = b", "cookie = \"session=a\\nurl = b\"\n"),
+        ];
+        for (session, expected) in cases {
+            assert_eq!(cookie_config(session), expected);
+        }
+    }
+}
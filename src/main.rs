@@ -1,54 +1,404 @@
 extern crate core;
 
+mod answers;
+mod bench;
+mod config;
 mod days;
+mod error;
+mod examples;
+mod input;
+mod runner;
+mod scaffold;
 mod utils;
+mod viz;
 
-use crate::days::get_day;
-use clap::{command, Parser};
-use std::fmt::Display;
-use std::fs;
+use crate::bench::{bench, median_change, Baseline};
+use crate::days::{get_registration, registered_days};
+use crate::examples::{check_example, find_examples};
+use crate::input::Source;
+use crate::runner::{
+    all_days, check_rows, check_source, parse_day_range, print_table, record_rows, run_days,
+    DayRange, Parts, Row,
+};
+use crate::utils::parallel::configure_threads;
+use crate::viz::image::Sequence;
+use crate::viz::record::{replay, Recorder};
+use crate::viz::Player;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::path::{Path, PathBuf};
 use std::process;
-use std::time::Instant;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Args {
-    /// Which day to execute, supports 1-25
-    #[arg(short, long)]
-    day: u8,
-    /// Which part to execute
-    #[arg(short, long)]
-    part: u8,
+    #[command(subcommand)]
+    command: Command,
+    /// Number of threads to run days and parts on, overriding `threads` in the config
+    #[arg(short = 'j', long, global = true, value_parser = clap::value_parser!(u16).range(1..))]
+    threads: Option<u16>,
 }
 
-fn get_input(day: &u8) -> String {
-    let path = format!("inputs/day_{}/input.txt", day);
+#[derive(clap::Args)]
+struct InputArgs {
+    /// Read the input from this file instead, or from stdin if it is -
+    #[arg(long, value_name = "PATH", conflicts_with = "example")]
+    input: Option<PathBuf>,
+    /// Read inputs/day_N/example_<EXAMPLE>.txt instead of the puzzle input
+    #[arg(long, num_args = 0..=1, default_missing_value = "1")]
+    example: Option<u8>,
+}
 
-    match fs::read_to_string(&path) {
-        Ok(contents) => contents,
-        Err(err) => {
-            eprintln!("Error reading file: {}", err);
-            process::exit(1);
+impl InputArgs {
+    fn source(&self) -> Source {
+        match (&self.input, self.example) {
+            (Some(path), _) => Source::Path(path.clone()),
+            (None, Some(number)) => Source::Example(number),
+            (None, None) => Source::Puzzle,
         }
     }
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Run days and print their answers
+    Run {
+        /// Which days to run, e.g. 7, 1-15 or 1,3,5-7, or every supported day if left out
+        #[arg(value_parser = parse_day_range)]
+        days: Option<DayRange>,
+        /// Which part to run
+        #[arg(short, long, value_enum, default_value_t = Parts::Both)]
+        part: Parts,
+        #[command(flatten)]
+        input: InputArgs,
+        /// Write answers to inputs/day_N/answers.txt, or example_N.answers.txt for an example
+        #[arg(long, conflicts_with = "input")]
+        record: bool,
+    },
+    /// Run days and compare their answers with the recorded ones, failing on a mismatch
+    Check {
+        /// Which days to check, e.g. 7, 1-15 or 1,3,5-7, or every supported day if left out
+        #[arg(value_parser = parse_day_range)]
+        days: Option<DayRange>,
+        /// Which part to check
+        #[arg(short, long, value_enum, default_value_t = Parts::Both)]
+        part: Parts,
+        /// Check inputs/day_N/example_<EXAMPLE>.txt against example_<EXAMPLE>.answers.txt
+        #[arg(long, num_args = 0..=1, default_missing_value = "1")]
+        example: Option<u8>,
+        /// Check every example of each day
+        #[arg(long, conflicts_with = "example")]
+        examples: bool,
+    },
+    /// Time a day over many iterations
+    Bench {
+        /// Which day to benchmark
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Which part to benchmark
+        #[arg(short, long, value_enum, default_value_t = Parts::Both)]
+        part: Parts,
+        #[command(flatten)]
+        input: InputArgs,
+        /// Timed iterations to run
+        #[arg(short = 'n', long, default_value_t = 100)]
+        iterations: usize,
+        /// Untimed iterations to run first
+        #[arg(long, default_value_t = 3)]
+        warmup: usize,
+        /// Also time parsing the input on its own
+        #[arg(long)]
+        parse: bool,
+        /// Add the results to a JSON baseline file
+        #[arg(long, value_name = "PATH")]
+        save_baseline: Option<PathBuf>,
+        /// Compare the results against a JSON baseline file
+        #[arg(long, value_name = "PATH")]
+        baseline: Option<PathBuf>,
+        /// Percentage slowdown in median time that counts as a regression
+        #[arg(long, default_value_t = 10.0, requires = "baseline")]
+        threshold: f64,
+    },
+    /// List the supported days
+    List,
+    /// Create a new day from days/template.rs
+    New {
+        /// The day to create
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// The puzzle's title
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// Watch a day solve its puzzle in the terminal
+    Viz {
+        /// Which day to watch
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Which part to watch
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        #[command(flatten)]
+        input: InputArgs,
+        /// Milliseconds between frames
+        #[arg(long, default_value_t = 50)]
+        delay: u64,
+        /// Record the frames to a file instead of playing them: an asciinema recording if it
+        /// ends in `.cast`, or numbered text frames otherwise
+        #[arg(long, value_name = "PATH")]
+        record: Option<PathBuf>,
+        /// Write each frame as a numbered image in this directory instead of playing them
+        #[arg(long, value_name = "DIR", conflicts_with = "record")]
+        images: Option<PathBuf>,
+        /// Write PNGs rather than PPMs, which needs the `png` feature
+        #[arg(long, requires = "images")]
+        png: bool,
+        /// Pixels along each side of a cell in the images
+        #[arg(long, default_value_t = 4, requires = "images")]
+        scale: usize,
+    },
+    /// Play back a recording made with `viz --record`
+    Replay {
+        path: PathBuf,
+        /// How many times faster than recorded to play
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+    },
+}
+
+/// Help text listing every registered day.
+fn days_help() -> String {
+    registered_days().fold("Days:\n".to_string(), |help, registration| {
+        let parts = match registration.parts {
+            1 => " (one part)",
+            _ => "",
+        };
+        help + &format!(
+            "  {:>2}  {}{}\n",
+            registration.number, registration.title, parts
+        )
+    })
+}
+
+fn exit_on_error<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        process::exit(1)
+    })
+}
+
 fn main() {
-    let args: Args = Args::parse();
-
-    let input = get_input(&args.day);
-    let day = match get_day(&args.day) {
-        Ok(day) => day,
-        Err(message) => {
-            println!("{}", message);
-            process::exit(1)
-        }
-    };
-    let start = Instant::now();
-    let answer: Box<dyn Display> = match args.part {
-        1 => Box::new(day.part_one(&input)),
-        2 => Box::new(day.part_two(&input)),
-        _ => panic!("Only parts 1 or 2 are supported."),
-    };
-    println!("Duration: {:?}, Answer: {}", start.elapsed(), answer);
+    let mut command = Args::command().after_help(days_help());
+    for name in ["run", "check", "bench", "viz"] {
+        command = command.mut_subcommand(name, |subcommand| subcommand.after_help(days_help()));
+    }
+    let args = Args::from_arg_matches(&command.get_matches()).unwrap_or_else(|err| err.exit());
+    exit_on_error(configure_threads(args.threads.map(usize::from)));
+
+    match args.command {
+        Command::Run {
+            days,
+            part,
+            input,
+            record,
+        } => {
+            let source = input.source();
+            let days = selected_days(days);
+            exit_on_error(check_source(&days, &source));
+            let rows = run_days(&days, part, &source);
+            if record {
+                exit_on_error(record_rows(&rows, &source));
+            }
+            print_rows(&rows);
+            if rows.iter().any(|row| row.outcome.is_err()) {
+                process::exit(1)
+            }
+        }
+        Command::Check {
+            days,
+            part,
+            examples: true,
+            ..
+        } => {
+            check_all_examples(&selected_days(days), part);
+        }
+        Command::Check {
+            days,
+            part,
+            example,
+            ..
+        } => {
+            let source = example.map(Source::Example).unwrap_or(Source::Puzzle);
+            let mut rows = run_days(&selected_days(days), part, &source);
+            let all_passed = exit_on_error(check_rows(&mut rows, &source));
+            print_table(&rows);
+            if !all_passed || rows.iter().any(|row| row.outcome.is_err()) {
+                process::exit(1)
+            }
+        }
+        Command::Bench {
+            day,
+            part,
+            input,
+            iterations,
+            warmup,
+            parse,
+            save_baseline,
+            baseline,
+            threshold,
+        } => {
+            let registration = exit_on_error(get_registration(&day));
+            let input = exit_on_error(input.source().read(&day));
+            let mut regressed = false;
+            for part in part.select(registration.parts) {
+                let benchmark = exit_on_error(
+                    bench(
+                        registration.day().as_ref(),
+                        (day, part),
+                        &input,
+                        warmup,
+                        iterations,
+                        parse,
+                    )
+                    .map_err(|err| err.in_part(day, part)),
+                );
+                println!("Day {}, part {}, {} iterations", day, part, iterations);
+                match &benchmark.parse {
+                    Some(parse) => println!("Parse: {}", parse),
+                    None if parse => eprintln!(
+                        "Warning: day {} has no separate parsing step, so only the total is timed",
+                        day
+                    ),
+                    None => (),
+                }
+                // Solving includes parsing, so this is the time for the whole part
+                println!("Total: {}", benchmark.solve);
+
+                if let Some(path) = &save_baseline {
+                    exit_on_error(Baseline::save(path, &benchmark));
+                    println!("Saved baseline to {}", path.display());
+                }
+                if let Some(path) = &baseline {
+                    let baseline = exit_on_error(Baseline::load(path));
+                    let Some(previous) = baseline.find(day, part) else {
+                        println!("No baseline recorded for day {}, part {}", day, part);
+                        continue;
+                    };
+                    let change = median_change(previous, &benchmark);
+                    println!(
+                        "Median {:?} -> {:?} ({:+.1}%)",
+                        previous.solve.median, benchmark.solve.median, change
+                    );
+                    if change > threshold {
+                        eprintln!("Regression: median is more than {}% slower", threshold);
+                        regressed = true;
+                    }
+                }
+            }
+            if regressed {
+                process::exit(1)
+            }
+        }
+        Command::List => {
+            for registration in registered_days() {
+                println!("{:>2}  {}", registration.number, registration.title);
+            }
+        }
+        Command::New { day, title } => {
+            let title = title.unwrap_or(format!("Day {}", day));
+            let source_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+            let paths = exit_on_error(scaffold::new_day(
+                &source_dir,
+                &input::day_dir(&day),
+                day,
+                &title,
+            ));
+            paths
+                .iter()
+                .for_each(|path| println!("Wrote {}", path.display()));
+        }
+        Command::Viz {
+            day,
+            part,
+            input,
+            delay,
+            record,
+            images,
+            png,
+            scale,
+        } => {
+            let solver = exit_on_error(get_registration(&day)).day();
+            let Some(visualization) = solver.visualization() else {
+                eprintln!("Error: Day {} has no visualization", day);
+                process::exit(1)
+            };
+            let input = exit_on_error(input.source().read(&day));
+            let delay = Duration::from_millis(delay);
+            let answer = match (record, images) {
+                (_, Some(directory)) => {
+                    let mut sequence = exit_on_error(Sequence::create(&directory, png, scale));
+                    let answer = visualization.visualize(&input, part, &mut sequence);
+                    let count = exit_on_error(sequence.finish());
+                    println!("Wrote {} images to {}", count, directory.display());
+                    answer
+                }
+                (Some(path), None) => {
+                    let mut recorder = exit_on_error(Recorder::create(&path, delay));
+                    let answer = visualization.visualize(&input, part, &mut recorder);
+                    exit_on_error(recorder.finish());
+                    println!("Wrote {}", path.display());
+                    answer
+                }
+                (None, None) => {
+                    let mut player = Player::new(delay);
+                    let answer = visualization.visualize(&input, part, &mut player);
+                    exit_on_error(player.finish());
+                    answer
+                }
+            };
+            match exit_on_error(answer.map_err(|err| err.in_part(day, part))) {
+                Some(answer) => println!("Answer: {}", answer),
+                None => println!("Stopped before the end"),
+            }
+        }
+        Command::Replay { path, speed } => exit_on_error(replay(&path, speed)),
+    }
+}
+
+fn selected_days(days: Option<DayRange>) -> Vec<u8> {
+    days.map(|range| range.0).unwrap_or_else(all_days)
+}
+
+/// Prints a single answer on its own, or a table for several.
+fn print_rows(rows: &[Row]) {
+    match rows {
+        [row] => match &row.outcome {
+            Ok(answer) => println!(
+                "Duration: {:?}, Answer: {}",
+                row.duration.unwrap_or_default(),
+                answer
+            ),
+            Err(message) => eprintln!("Error: Day {}, part {}: {}", row.day, row.part, message),
+        },
+        _ => print_table(rows),
+    }
+}
+
+fn check_all_examples(days: &[u8], parts: Parts) {
+    let mut failures = vec![];
+    let mut checked = 0;
+    for number in days {
+        let registration = exit_on_error(get_registration(number));
+        let day = registration.day();
+        let selected = parts.select(registration.parts);
+        for example in exit_on_error(find_examples(&input::day_dir(number))) {
+            checked += 1;
+            failures.extend(check_example(*number, day.as_ref(), &example, &selected));
+        }
+    }
+    failures.iter().for_each(|failure| println!("{}", failure));
+    println!("Checked {} examples, {} failures", checked, failures.len());
+    if !failures.is_empty() {
+        process::exit(1)
+    }
 }
@@ -0,0 +1,269 @@
+use crate::answers::{Answers, Verdict};
+use crate::days::{get_day, get_registration, registered_days};
+use crate::input::Source;
+use crate::utils::parallel::par_map;
+use std::fmt::Display;
+use std::io;
+use std::time::{Duration, Instant};
+
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Result<String, String>,
+    pub duration: Option<Duration>,
+    pub verdict: Option<Verdict>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayRange(pub Vec<u8>);
+
+/// Parses a day selection such as `7`, `1-15` or `1,3,5-7` into a sorted list of days.
+pub fn parse_day_range(value: &str) -> Result<DayRange, String> {
+    let mut days = vec![];
+    for item in value.split(',').map(str::trim) {
+        let (start, end) = match item.split_once('-') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (item, item),
+        };
+        let start: u8 = start
+            .parse()
+            .map_err(|_| format!("'{}' is not a valid day", start))?;
+        let end: u8 = end
+            .parse()
+            .map_err(|_| format!("'{}' is not a valid day", end))?;
+        if start == 0 || end > 25 || start > end {
+            return Err(format!("'{}' is not a range of days within 1-25", item));
+        }
+        days.extend(start..=end);
+    }
+    days.sort();
+    days.dedup();
+    Ok(DayRange(days))
+}
+
+/// Which parts of each day to run.
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum Parts {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    Both,
+}
+
+impl Parts {
+    /// The selected parts of a day that has `available` parts. Asking for a single part the
+    /// day doesn't have still returns it, so it can be reported.
+    pub fn select(&self, available: u8) -> Vec<u8> {
+        match self {
+            Parts::One => vec![1],
+            Parts::Two => vec![2],
+            Parts::Both => (1..=available).collect(),
+        }
+    }
+}
+
+pub fn all_days() -> Vec<u8> {
+    registered_days().map(|day| day.number).collect()
+}
+
+/// Checks that an input given by path goes to a single day, since stdin can only be read once
+/// and no file holds more than one day's input.
+pub fn check_source(days: &[u8], source: &Source) -> Result<(), String> {
+    match (source, days) {
+        (Source::Path(_) | Source::Stdin, [_]) | (Source::Puzzle | Source::Example(_), _) => Ok(()),
+        _ => Err(format!(
+            "--input needs exactly one day, but {} were selected",
+            days.len()
+        )),
+    }
+}
+
+/// Runs the selected parts of every given day, carrying on past days that fail to load. The
+/// parts run in parallel, but the rows come back in day and part order.
+pub fn run_days(days: &[u8], parts: Parts, source: &Source) -> Vec<Row> {
+    // Inputs are read up front and in order, since reading one may fetch it from the server
+    let inputs: Vec<Result<String, String>> = days
+        .iter()
+        .map(|day| {
+            source
+                .read(day)
+                .map_err(|err| format!("Error reading input: {}", err))
+        })
+        .collect();
+    let jobs: Vec<(u8, u8, &Result<String, String>)> = days
+        .iter()
+        .zip(&inputs)
+        .flat_map(|(day, input)| {
+            let available = get_registration(day).map_or(2, |registration| registration.parts);
+            parts
+                .select(available)
+                .into_iter()
+                .map(move |part| (*day, part, input))
+        })
+        .collect();
+    par_map(&jobs, |(day, part, input)| run_part(*day, *part, input))
+}
+
+fn run_part(day: u8, part: u8, input: &Result<String, String>) -> Row {
+    let error = |message: String| Row {
+        day,
+        part,
+        outcome: Err(message),
+        duration: None,
+        verdict: None,
+    };
+    let solver = get_day(&day);
+    let (input, solver) = match (input, &solver) {
+        (Ok(input), Ok(solver)) => (input, solver),
+        (_, Err(message)) | (Err(message), _) => return error(message.clone()),
+    };
+    let available = get_registration(&day).map_or(2, |registration| registration.parts);
+    if part > available {
+        return error(format!("Day {} only has {} part", day, available));
+    }
+    let start = Instant::now();
+    let answer = match part {
+        1 => solver.part_one(input),
+        _ => solver.part_two(input),
+    };
+    Row {
+        day,
+        part,
+        outcome: answer.map_err(|err| err.to_string()),
+        duration: Some(start.elapsed()),
+        verdict: None,
+    }
+}
+
+/// Compares each answered row against the recorded answers for its input, returning whether all
+/// of them either passed or had nothing to compare against.
+pub fn check_rows(rows: &mut [Row], source: &Source) -> io::Result<bool> {
+    let mut all_passed = true;
+    for row in rows.iter_mut() {
+        if let (Ok(answer), Some(path)) = (&row.outcome, Answers::path(&row.day, source)) {
+            let verdict = Answers::load(&path)?.check(row.part, answer);
+            all_passed &= !matches!(verdict, Verdict::Fail { .. });
+            row.verdict = Some(verdict);
+        }
+    }
+    Ok(all_passed)
+}
+
+/// Writes every successful answer to the answers file for its input.
+pub fn record_rows(rows: &[Row], source: &Source) -> io::Result<()> {
+    for row in rows {
+        if let (Ok(answer), Some(path)) = (&row.outcome, Answers::path(&row.day, source)) {
+            let mut answers = Answers::load(&path)?;
+            answers.set(row.part, answer);
+            answers.save(&path)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn print_table(rows: &[Row]) {
+    let show_verdicts = rows.iter().any(|row| row.verdict.is_some());
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![
+                row.day.to_string(),
+                row.part.to_string(),
+                match &row.outcome {
+                    Ok(answer) => answer.clone(),
+                    Err(message) => message.clone(),
+                },
+                row.duration
+                    .map(|duration| format!("{:?}", duration))
+                    .unwrap_or("-".to_string()),
+            ];
+            if show_verdicts {
+                cells.push(
+                    row.verdict
+                        .as_ref()
+                        .map(|verdict| verdict.to_string())
+                        .unwrap_or("-".to_string()),
+                );
+            }
+            cells
+        })
+        .collect();
+
+    let header = ["Day", "Part", "Answer", "Duration", "Check"];
+    let header = &header[..if show_verdicts { 5 } else { 4 }];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            cells
+                .iter()
+                .map(|row| row[column].len())
+                .chain([header[column].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    print_row(header, &widths);
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    for row in &cells {
+        print_row(row, &widths);
+    }
+}
+
+fn print_row(row: &[impl Display], widths: &[usize]) {
+    let line = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    println!("{}", line.trim_end());
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_day_range() {
+        let cases = vec![
+            ("7", Ok(vec![7])),
+            ("1-3", Ok(vec![1, 2, 3])),
+            ("5-6,1,2-3", Ok(vec![1, 2, 3, 5, 6])),
+            ("3-1", Err(())),
+            ("0-4", Err(())),
+            ("24-26", Err(())),
+            ("x", Err(())),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_day_range(input).map(|range| range.0).map_err(|_| ()),
+                expected,
+                "{}",
+                input
+            )
+        }
+    }
+
+    #[test]
+    fn test_check_source() {
+        let stdin = Source::Path("-".into());
+        let cases = vec![
+            (vec![1], stdin.clone(), true),
+            (vec![1, 2], stdin, false),
+            (vec![], Source::Path("input.txt".into()), false),
+            (vec![1, 2], Source::Example(1), true),
+            (vec![1, 2], Source::Puzzle, true),
+        ];
+        for (days, source, expected) in cases {
+            assert_eq!(check_source(&days, &source).is_ok(), expected, "{:?}", days);
+        }
+    }
+}
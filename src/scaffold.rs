@@ -0,0 +1,267 @@
+use crate::answers::Answers;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = include_str!("days/template.rs");
+
+// Compiled for its own tests, so a new day starts out passing them
+#[cfg(test)]
+#[path = "days/template.rs"]
+mod template;
+const REGISTRY_START: &str = "register_days! {\n";
+
+const NUMBER_WORDS: [&str; 25] = [
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+    "twentyone",
+    "twentytwo",
+    "twentythree",
+    "twentyfour",
+    "twentyfive",
+];
+
+/// The module name for a day, e.g. `sixteen` for day 16.
+pub fn module_name(day: u8) -> Result<&'static str, String> {
+    NUMBER_WORDS
+        .get((day as usize).wrapping_sub(1))
+        .copied()
+        .ok_or_else(|| format!("Day {} is outside 1-25", day))
+}
+
+/// The struct name for a day, e.g. `DaySixteen` for day 16.
+pub fn struct_name(day: u8) -> Result<String, String> {
+    let module = module_name(day)?;
+    // Compound words keep their second capital, as in DayTwentyOne
+    let words = match module.strip_prefix("twenty") {
+        Some(rest) if !rest.is_empty() => vec!["twenty", rest],
+        _ => vec![module],
+    };
+    Ok(words.iter().fold("Day".to_string(), |mut name, word| {
+        name.push_str(&word[..1].to_uppercase());
+        name.push_str(&word[1..]);
+        name
+    }))
+}
+
+/// Creates a new day from `days/template.rs`: the module under `source_dir/days`, its `mod` line
+/// and its line in the `register_days!` table in `source_dir/days.rs`, and `day_dir` with a
+/// placeholder example and answers for the example harness. Returns the paths written. Nothing
+/// is written if the day already exists.
+pub fn new_day(
+    source_dir: &Path,
+    day_dir: &Path,
+    day: u8,
+    title: &str,
+) -> Result<Vec<PathBuf>, String> {
+    let module = module_name(day)?;
+    let name = struct_name(day)?;
+    let module_path = source_dir.join("days").join(format!("{}.rs", module));
+    let registry_path = source_dir.join("days.rs");
+
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+    let registry = fs::read_to_string(&registry_path)
+        .map_err(|err| format!("Couldn't read {}: {}", registry_path.display(), err))?;
+    let registry = register(&declare(&registry, module)?, day, module, &name, title)?;
+
+    let write = |path: &Path, contents: &str| {
+        fs::write(path, contents)
+            .map_err(|err| format!("Couldn't write {}: {}", path.display(), err))
+    };
+    write(&module_path, &TEMPLATE.replace("DayXXXX", &name))?;
+    write(&registry_path, &registry)?;
+
+    fs::create_dir_all(day_dir)
+        .map_err(|err| format!("Couldn't create {}: {}", day_dir.display(), err))?;
+    let mut paths = vec![module_path, registry_path];
+    for placeholder in ["example_1.txt".to_string(), Answers::example_name(1)] {
+        let path = day_dir.join(placeholder);
+        if !path.exists() {
+            write(&path, "")?;
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Adds a day's `mod` line to the others, which rustfmt keeps in alphabetical order.
+fn declare(registry: &str, module: &str) -> Result<String, String> {
+    let declaration = format!("mod {};\n", module);
+    let mut insert_at = None;
+    let mut offset = 0;
+    for line in registry.split_inclusive('\n') {
+        let existing = line
+            .strip_prefix("mod ")
+            .and_then(|rest| rest.strip_suffix(";\n"));
+        if let Some(existing) = existing {
+            if existing == module {
+                return Err(format!("Module {} is already declared", module));
+            }
+            if existing > module {
+                insert_at = Some(offset);
+                break;
+            }
+            insert_at = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    let insert_at = insert_at.ok_or("Couldn't find the day modules")?;
+    Ok([&registry[..insert_at], &declaration, &registry[insert_at..]].concat())
+}
+
+/// Adds a day to the `register_days!` table, keeping it in day order.
+fn register(
+    registry: &str,
+    day: u8,
+    module: &str,
+    name: &str,
+    title: &str,
+) -> Result<String, String> {
+    let start = registry
+        .find(REGISTRY_START)
+        .ok_or("Couldn't find the register_days! table")?
+        + REGISTRY_START.len();
+    let end = start
+        + registry[start..]
+            .find("}\n")
+            .ok_or("Couldn't find the end of the register_days! table")?;
+
+    let mut insert_at = start;
+    for line in registry[start..end].split_inclusive('\n') {
+        let number: u8 = line
+            .trim()
+            .split_once(" =>")
+            .and_then(|(number, _)| number.parse().ok())
+            .ok_or_else(|| format!("Couldn't read the registration '{}'", line.trim()))?;
+        if number == day {
+            return Err(format!("Day {} is already registered", day));
+        }
+        if number > day {
+            break;
+        }
+        insert_at += line.len();
+    }
+
+    // The last day of the year only has one part
+    let parts = if day == 25 { ", parts: 1" } else { "" };
+    let entry = format!(
+        "    {} => {}::{}, {:?}{};\n",
+        day, module, name, title, parts
+    );
+    Ok([&registry[..insert_at], &entry, &registry[insert_at..]].concat())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_names() {
+        let cases = vec![
+            (1, "one", "DayOne"),
+            (16, "sixteen", "DaySixteen"),
+            (20, "twenty", "DayTwenty"),
+            (21, "twentyone", "DayTwentyOne"),
+        ];
+        for (day, module, name) in cases {
+            assert_eq!(module_name(day), Ok(module));
+            assert_eq!(struct_name(day), Ok(name.to_string()));
+        }
+        assert!(module_name(0).is_err());
+        assert!(module_name(26).is_err());
+    }
+
+    #[test]
+    fn test_register() {
+        let registry = concat!(
+            "register_days! {\n",
+            "    1 => one::DayOne, \"A\";\n",
+            "    3 => three::DayThree, \"C\";\n",
+            "}\n",
+        );
+        assert_eq!(
+            register(registry, 2, "two", "DayTwo", "B"),
+            Ok(concat!(
+                "register_days! {\n",
+                "    1 => one::DayOne, \"A\";\n",
+                "    2 => two::DayTwo, \"B\";\n",
+                "    3 => three::DayThree, \"C\";\n",
+                "}\n",
+            )
+            .to_string())
+        );
+        assert!(register(registry, 3, "three", "DayThree", "C").is_err());
+        assert!(register(registry, 25, "twentyfive", "DayTwentyFive", "Y")
+            .unwrap()
+            .contains("    25 => twentyfive::DayTwentyFive, \"Y\", parts: 1;\n}"));
+    }
+
+    #[test]
+    fn test_declare() {
+        let registry = "use crate::error;\n\nmod eight;\nmod one;\nmod two;\n\nstatic X: u8 = 1;\n";
+        let cases = vec![
+            ("five", "mod eight;\nmod five;\nmod one;"),
+            ("eighteen", "mod eight;\nmod eighteen;\nmod one;"),
+            ("twentyone", "mod one;\nmod twentyone;\nmod two;"),
+            ("zero", "mod two;\nmod zero;\n\n"),
+            ("eleven", "mod eight;\nmod eleven;\nmod one;"),
+        ];
+        for (module, expected) in cases {
+            assert!(
+                declare(registry, module).unwrap().contains(expected),
+                "{}",
+                module
+            );
+        }
+        assert!(declare(registry, "one").is_err());
+        assert!(declare("fn main() {}\n", "one").is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("aoc24-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let source_dir = root.join("src");
+        fs::create_dir_all(source_dir.join("days")).unwrap();
+        fs::write(source_dir.join("days.rs"), include_str!("days.rs")).unwrap();
+        let day_dir = root.join("inputs").join("day_16");
+
+        new_day(&source_dir, &day_dir, 16, "Reindeer Maze").unwrap();
+        let module = fs::read_to_string(source_dir.join("days").join("sixteen.rs")).unwrap();
+        assert!(module.contains("pub struct DaySixteen {}"));
+        assert!(!module.contains("DayXXXX"));
+        let registry = fs::read_to_string(source_dir.join("days.rs")).unwrap();
+        assert!(registry.contains("    16 => sixteen::DaySixteen, \"Reindeer Maze\";\n}"));
+        assert!(registry.contains("mod six;\nmod sixteen;\nmod ten;\n"));
+        assert!(day_dir.join("example_1.txt").exists());
+        assert!(day_dir.join("example_1.answers.txt").exists());
+
+        assert!(new_day(&source_dir, &day_dir, 16, "Reindeer Maze").is_err());
+        assert_eq!(
+            fs::read_to_string(source_dir.join("days.rs")).unwrap(),
+            registry
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
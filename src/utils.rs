@@ -1,37 +1,29 @@
-use crossterm::terminal::ClearType;
-use crossterm::{cursor, terminal, ExecutableCommand};
-use std::collections::HashMap;
-use std::fmt::{Debug, Formatter};
-use std::io::{stdout, IsTerminal, Write};
-use std::thread::sleep;
-use std::time::Duration;
-
-#[allow(dead_code)]
-pub fn draw_frame(string: &str, delay: Option<u64>) {
-    let mut stdout = stdout();
-    stdout.is_terminal();
-
-    // Clear the terminal
-    stdout.execute(terminal::Clear(ClearType::All)).unwrap();
-
-    // Move the cursor to the top-left corner
-    stdout.execute(cursor::MoveTo(0, 0)).unwrap();
+pub mod big;
+pub mod checked;
+pub mod linalg;
+pub mod math;
+pub mod parallel;
+pub mod parse;
+pub mod region;
+pub mod search;
+pub mod simulate;
 
-    println!("{string}");
-
-    // Flush to ensure the output is displayed
-    stdout.flush().unwrap();
-
-    sleep(Duration::from_millis(delay.unwrap_or(9)));
-}
+use crate::error::{self, Error};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 
-#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug)]
+/// A position or offset on a grid, with `y` growing downwards.
+#[derive(Eq, Hash, PartialEq, Clone, Copy, Debug, Default)]
 pub struct Coord {
     pub x: i64,
     pub y: i64,
 }
 
 impl Coord {
+    pub const ORIGIN: Coord = Coord { x: 0, y: 0 };
+
     pub fn new(x: i64, y: i64) -> Coord {
         Coord { x, y }
     }
@@ -47,33 +39,362 @@ impl Coord {
         self.plus(offset, 0)
     }
 
-    pub fn plus_y(&self, offset: i64) -> Coord {
-        self.plus(0, offset)
+    #[allow(dead_code, reason = "the usual A* heuristic, which no day uses yet")]
+    pub fn manhattan(&self, other: &Coord) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    #[allow(dead_code, reason = "the distance when diagonal steps count as one")]
+    pub fn chebyshev(&self, other: &Coord) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Wraps the coordinate onto a `width` by `height` torus starting at the origin.
+    pub fn wrap(&self, width: i64, height: i64) -> Coord {
+        Coord {
+            x: self.x.rem_euclid(width),
+            y: self.y.rem_euclid(height),
+        }
+    }
+
+    /// The four orthogonal neighbours, clockwise from the one above.
+    pub fn neighbours4(&self) -> [Coord; 4] {
+        Direction::ALL.map(|direction| *self + direction.offset())
+    }
+
+    /// All eight neighbours, clockwise from the one above.
+    pub fn neighbours8(&self) -> [Coord; 8] {
+        [
+            self.plus(0, -1),
+            self.plus(1, -1),
+            self.plus(1, 0),
+            self.plus(1, 1),
+            self.plus(0, 1),
+            self.plus(-1, 1),
+            self.plus(-1, 0),
+            self.plus(-1, -1),
+        ]
     }
 }
 
-#[derive(Clone)]
+/// Coordinates are ordered as they're read, by row and then by column.
+impl Ord for Coord {
+    fn cmp(&self, other: &Coord) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Coord {
+    fn partial_cmp(&self, other: &Coord) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<(i64, i64)> for Coord {
+    fn from((x, y): (i64, i64)) -> Coord {
+        Coord { x, y }
+    }
+}
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, other: Coord) -> Coord {
+        self.plus(other.x, other.y)
+    }
+}
+
+impl AddAssign for Coord {
+    fn add_assign(&mut self, other: Coord) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, other: Coord) -> Coord {
+        self.plus(-other.x, -other.y)
+    }
+}
+
+impl SubAssign for Coord {
+    fn sub_assign(&mut self, other: Coord) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Coord {
+    type Output = Coord;
+
+    fn neg(self) -> Coord {
+        Coord::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Coord {
+    type Output = Coord;
+
+    fn mul(self, factor: i64) -> Coord {
+        Coord::new(self.x * factor, self.y * factor)
+    }
+}
+
+/// A dense, row-major grid with a fixed width and height.
+#[derive(Clone, PartialEq, Eq)]
 pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Builds a grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+            width,
+        })
+    }
+
+    /// Parses a character map, one row per line, turning each character into a cell with `cell`.
+    /// The positions of any `markers` characters are recorded as they're found.
+    pub fn parse<F>(input: &str, markers: &str, mut cell: F) -> error::Result<ParsedGrid<T>>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        Grid::parse_widened(input, markers, 1, |letter, _| cell(letter))
+    }
+
+    /// Parses a character map where each character becomes `width` cells side by side.
+    /// `cell` is given the character and the offset within its cells, and markers are recorded
+    /// at the first of them.
+    pub fn parse_widened<F>(
+        input: &str,
+        markers: &str,
+        width: usize,
+        mut cell: F,
+    ) -> error::Result<ParsedGrid<T>>
+    where
+        F: FnMut(char, usize) -> Option<T>,
+    {
+        let mut cells = vec![];
+        let mut found = vec![];
+        let mut grid_width = None;
+        let mut height = 0;
+        // Blank lines at the end aren't rows, but any earlier ones are
+        let rows = input.trim_end_matches(['\r', '\n']);
+        for (y, line) in rows.lines().enumerate() {
+            let mut line_width = 0;
+            for (x, (index, letter)) in line.char_indices().enumerate() {
+                if markers.contains(letter) {
+                    found.push((letter, Coord::new((x * width) as i64, y as i64)));
+                }
+                for offset in 0..width {
+                    cells.push(cell(letter, offset).ok_or_else(|| {
+                        Error::at(input, &line[index..], format!("unexpected '{}'", letter))
+                    })?);
+                }
+                line_width += width;
+            }
+            match grid_width {
+                None => grid_width = Some(line_width),
+                Some(expected) if expected != line_width => {
+                    return Err(Error::at(
+                        input,
+                        line,
+                        format!(
+                            "expected a row {} cells wide, found {}",
+                            expected, line_width
+                        ),
+                    ))
+                }
+                _ => (),
+            }
+            height += 1;
+        }
+        Ok(ParsedGrid {
+            grid: Grid {
+                cells,
+                width: grid_width.unwrap_or(0),
+                height,
+            },
+            markers: found,
+        })
+    }
+
+    pub fn width(&self) -> i64 {
+        self.width as i64
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height as i64
+    }
+
+    pub fn contains(&self, coord: &Coord) -> bool {
+        (0..self.width()).contains(&coord.x) && (0..self.height()).contains(&coord.y)
+    }
+
+    fn index_of(&self, coord: &Coord) -> Option<usize> {
+        self.contains(coord)
+            .then(|| coord.y as usize * self.width + coord.x as usize)
+    }
+
+    /// Sets a cell. Panics if `coord` is outside the grid.
+    pub fn set(&mut self, coord: Coord, value: T) {
+        self[coord] = value;
+    }
+
+    pub fn get(&self, coord: &Coord) -> Option<&T> {
+        self.index_of(coord).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, coord: &Coord) -> Option<&mut T> {
+        self.index_of(coord).map(|index| &mut self.cells[index])
+    }
+
+    /// Every cell with its coordinate, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(index, value)| {
+            let coord = Coord::new((index % width) as i64, (index / width) as i64);
+            (coord, value)
+        })
+    }
+
+    /// A grid of the same size with each cell replaced by `f` of its coordinate and value.
+    pub fn map<U>(&self, mut f: impl FnMut(Coord, &T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.iter().map(|(coord, value)| f(coord, value)).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    #[allow(
+        dead_code,
+        reason = "for lookups by row number, which no day has needed yet"
+    )]
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells in column `x`, from top to bottom.
+    #[allow(
+        dead_code,
+        reason = "for scanning down a column, which no day has needed yet"
+    )]
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        (x < self.width).then(|| self.cells.iter().skip(x).step_by(self.width))
+    }
+}
+
+/// A grid read by [`Grid::parse`], along with where its marker characters were found.
+pub struct ParsedGrid<T> {
+    pub grid: Grid<T>,
+    pub markers: Vec<(char, Coord)>,
+}
+
+impl<T> ParsedGrid<T> {
+    /// The first position of `marker`, in row-major order.
+    pub fn find(&self, marker: char) -> Option<Coord> {
+        self.find_all(marker).next()
+    }
+
+    pub fn find_all(&self, marker: char) -> impl Iterator<Item = Coord> + '_ {
+        self.markers
+            .iter()
+            .filter(move |(letter, _)| *letter == marker)
+            .map(|(_, coord)| *coord)
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        self.get(&coord)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", coord))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        self.get_mut(&coord)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", coord))
+    }
+}
+
+impl<T> Debug for Grid<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for value in row {
+                write!(f, "{:?}", value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A grid stored as a map from coordinates, for grids that are unbounded or mostly empty.
+#[derive(Clone)]
+pub struct SparseGrid<T> {
     grid: HashMap<Coord, T>,
     pub top_left: Coord,
     pub bottom_right: Coord,
 }
 
-impl<T> Grid<T> {
-    pub fn new() -> Grid<T> {
-        Grid {
+#[allow(
+    dead_code,
+    reason = "kept for unbounded maps, which no day has needed yet"
+)]
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid {
             grid: HashMap::new(),
             top_left: Coord::new(0, 0),
             bottom_right: Coord::new(0, 0),
         }
     }
 
+    /// The number of columns from the leftmost cell set to the rightmost, inclusive.
     pub fn width(&self) -> i64 {
-        (self.top_left.x - self.bottom_right.x).abs()
+        self.bottom_right.x - self.top_left.x + 1
     }
 
+    /// The number of rows from the topmost cell set to the bottommost, inclusive.
     pub fn height(&self) -> i64 {
-        (self.top_left.y - self.bottom_right.y).abs()
+        self.bottom_right.y - self.top_left.y + 1
     }
 
     pub fn set(&mut self, coord: Coord, value: T) {
@@ -89,19 +410,19 @@ impl<T> Grid<T> {
         self.grid.get(coord)
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&Coord, &T)> {
-        self.grid.iter()
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.grid.iter().map(|(coord, value)| (*coord, value))
     }
 }
 
-impl<T> Debug for Grid<T>
+impl<T> Debug for SparseGrid<T>
 where
     T: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let result = (0..=self.width())
+        let result = (self.top_left.y..=self.bottom_right.y)
             .map(|y| {
-                let line = (0..=self.height())
+                let line = (self.top_left.x..=self.bottom_right.x)
                     .map(|x| {
                         self.get(&Coord { x, y })
                             .map(|value| format!("{:?}", value))
@@ -116,7 +437,8 @@ where
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Debug)]
+/// A diagonal direction, with `y` growing downwards like [`Coord`].
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, PartialOrd, Ord)]
 pub enum Diagonal {
     TL,
     TR,
@@ -124,7 +446,61 @@ pub enum Diagonal {
     BL,
 }
 
-#[derive(Eq, PartialEq, Hash, Debug)]
+#[allow(
+    dead_code,
+    reason = "its turns mirror `Direction`'s for diagonal walks"
+)]
+impl Diagonal {
+    /// Every diagonal, clockwise from the top left.
+    pub const ALL: [Diagonal; 4] = [Diagonal::TL, Diagonal::TR, Diagonal::BR, Diagonal::BL];
+
+    pub fn turn_right(&self) -> Diagonal {
+        match self {
+            Diagonal::TL => Diagonal::TR,
+            Diagonal::TR => Diagonal::BR,
+            Diagonal::BR => Diagonal::BL,
+            Diagonal::BL => Diagonal::TL,
+        }
+    }
+
+    pub fn turn_left(&self) -> Diagonal {
+        self.opposite().turn_right()
+    }
+
+    pub fn opposite(&self) -> Diagonal {
+        self.turn_right().turn_right()
+    }
+
+    /// The vertical and horizontal directions this diagonal is made of.
+    pub fn directions(&self) -> (Direction, Direction) {
+        match self {
+            Diagonal::TL => (Direction::Up, Direction::Left),
+            Diagonal::TR => (Direction::Up, Direction::Right),
+            Diagonal::BR => (Direction::Down, Direction::Right),
+            Diagonal::BL => (Direction::Down, Direction::Left),
+        }
+    }
+
+    pub fn offset(&self) -> Coord {
+        let (vertical, horizontal) = self.directions();
+        vertical.offset() + horizontal.offset()
+    }
+}
+
+impl Display for Diagonal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let arrow = match self {
+            Diagonal::TL => '↖',
+            Diagonal::TR => '↗',
+            Diagonal::BR => '↘',
+            Diagonal::BL => '↙',
+        };
+        write!(f, "{}", arrow)
+    }
+}
+
+/// An orthogonal direction, with `y` growing downwards like [`Coord`].
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, PartialOrd, Ord)]
 pub enum Direction {
     Up,
     Right,
@@ -133,8 +509,28 @@ pub enum Direction {
 }
 
 impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Reads a direction from an arrow (`^`), a capital letter (`U`) or a compass point (`N`).
     pub fn new(letter: &char) -> Option<Self> {
         match letter {
+            'U' | 'N' => Some(Self::Up),
+            'R' | 'E' => Some(Self::Right),
+            'D' | 'S' => Some(Self::Down),
+            'L' | 'W' => Some(Self::Left),
+            _ => Self::from_arrow(letter),
+        }
+    }
+
+    /// Reads a direction from one of the arrows `^`, `>`, `v` and `<`, and nothing else.
+    pub fn from_arrow(arrow: &char) -> Option<Self> {
+        match arrow {
             '^' => Some(Self::Up),
             '>' => Some(Self::Right),
             'v' => Some(Self::Down),
@@ -142,4 +538,219 @@ impl Direction {
             _ => None,
         }
     }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    #[allow(dead_code, reason = "for turning left, which no patrol has done yet")]
+    pub fn turn_left(&self) -> Direction {
+        self.opposite().turn_right()
+    }
+
+    #[allow(dead_code, reason = "for retracing a path back the way it came")]
+    pub fn opposite(&self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    pub fn offset(&self) -> Coord {
+        match self {
+            Direction::Up => Coord::new(0, -1),
+            Direction::Right => Coord::new(1, 0),
+            Direction::Down => Coord::new(0, 1),
+            Direction::Left => Coord::new(-1, 0),
+        }
+    }
+
+    /// The diagonal between this direction and a perpendicular one, such as `TR` for up and
+    /// right. Parallel directions have no diagonal.
+    #[allow(dead_code, reason = "for combining two moves into one diagonal step")]
+    pub fn with(&self, other: Direction) -> Option<Diagonal> {
+        Diagonal::ALL.into_iter().find(|diagonal| {
+            let (vertical, horizontal) = diagonal.directions();
+            (vertical, horizontal) == (*self, other) || (horizontal, vertical) == (*self, other)
+        })
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let arrow = match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        };
+        write!(f, "{}", arrow)
+    }
+}
+
+impl Add<Direction> for Coord {
+    type Output = Coord;
+
+    fn add(self, direction: Direction) -> Coord {
+        self + direction.offset()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_coord() {
+        let a = Coord::new(1, 2);
+        let b = Coord::from((4, -2));
+        assert_eq!(a + b, Coord::new(5, 0));
+        assert_eq!(a - b, Coord::new(-3, 4));
+        assert_eq!(-a, Coord::new(-1, -2));
+        assert_eq!(a * 3, Coord::new(3, 6));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(Coord::new(-1, 12).wrap(11, 7), Coord::new(10, 5));
+        assert_eq!(a.to_string(), "(1, 2)");
+        assert!(Coord::new(5, 0) < Coord::new(0, 1));
+        assert_eq!(a.neighbours4()[0], Coord::new(1, 1));
+        assert!(a
+            .neighbours8()
+            .iter()
+            .all(|neighbour| a.chebyshev(neighbour) == 1));
+    }
+
+    #[test]
+    fn test_direction() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.offset(), -direction.opposite().offset());
+            assert_eq!(
+                Direction::new(&direction.to_string().chars().next().unwrap()),
+                Some(direction)
+            );
+        }
+        let cases = vec![
+            ('^', Some(Direction::Up)),
+            ('E', Some(Direction::Right)),
+            ('D', Some(Direction::Down)),
+            ('<', Some(Direction::Left)),
+            ('d', None),
+            ('V', None),
+            ('x', None),
+        ];
+        for (letter, expected) in cases {
+            assert_eq!(Direction::new(&letter), expected);
+        }
+        let cases = vec![
+            ('v', Some(Direction::Down)),
+            ('^', Some(Direction::Up)),
+            ('U', None),
+            ('r', None),
+        ];
+        for (arrow, expected) in cases {
+            assert_eq!(Direction::from_arrow(&arrow), expected);
+        }
+        assert_eq!(Coord::new(2, 2) + Direction::Up, Coord::new(2, 1));
+    }
+
+    #[test]
+    fn test_diagonal() {
+        assert_eq!(Diagonal::TL.opposite(), Diagonal::BR);
+        assert_eq!(Diagonal::TR.turn_left(), Diagonal::TL);
+        assert_eq!(Diagonal::BL.offset(), Coord::new(-1, 1));
+        assert_eq!(Direction::Right.with(Direction::Up), Some(Diagonal::TR));
+        assert_eq!(Direction::Down.with(Direction::Left), Some(Diagonal::BL));
+        assert_eq!(Direction::Up.with(Direction::Down), None);
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(&Coord::new(2, 1)), Some(&6));
+        assert_eq!(grid.get(&Coord::new(3, 0)), None);
+        assert_eq!(grid.get(&Coord::new(0, -1)), None);
+        grid.set(Coord::new(1, 0), 7);
+        assert_eq!(grid[Coord::new(1, 0)], 7);
+        assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.rows().nth(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.column(1).unwrap().collect::<Vec<_>>(), vec![&7, &5]);
+        assert!(grid.column(3).is_none());
+        assert_eq!(grid.iter().nth(4), Some((Coord::new(1, 1), &5)));
+        assert_eq!(format!("{:?}", grid), "173\n456\n");
+        assert_eq!(
+            grid.map(|coord, value| value + coord.y),
+            Grid::from_rows(vec![vec![1, 7, 3], vec![5, 6, 7]]).unwrap()
+        );
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = Grid::parse("12\n0^\n", "0^", |letter| match letter {
+            '^' => Some(0),
+            _ => letter.to_digit(10),
+        })
+        .unwrap();
+        assert_eq!(
+            parsed.grid,
+            Grid::from_rows(vec![vec![1, 2], vec![0, 0]]).unwrap()
+        );
+        assert_eq!(parsed.find('^'), Some(Coord::new(1, 1)));
+        assert_eq!(
+            parsed.find_all('0').collect::<Vec<_>>(),
+            vec![Coord::new(0, 1)]
+        );
+        assert_eq!(parsed.find('1'), None);
+        for input in ["12\n34\n\n", "12\r\n34\r\n\r\n\r\n"] {
+            let parsed = Grid::parse(input, "", |letter| letter.to_digit(10)).unwrap();
+            assert_eq!((parsed.grid.width(), parsed.grid.height()), (2, 2));
+        }
+
+        let cases = vec![
+            (
+                "12\n\n34",
+                "line 2, column 1: expected a row 2 cells wide, found 0",
+            ),
+            ("12\n3x", "line 2, column 2: unexpected 'x'"),
+            (
+                "123\n45\n",
+                "line 2, column 1: expected a row 3 cells wide, found 2",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                Grid::parse(input, "", |letter| letter.to_digit(10))
+                    .err()
+                    .map(|err| err.to_string()),
+                Some(expected.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_widened() {
+        let parsed = Grid::parse_widened("#@", "@", 2, |letter, offset| match (letter, offset) {
+            ('@', 1) => Some('.'),
+            _ => Some(letter),
+        })
+        .unwrap();
+        assert_eq!(parsed.grid.rows().next(), Some(&['#', '#', '@', '.'][..]));
+        assert_eq!(parsed.find('@'), Some(Coord::new(2, 0)));
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new();
+        grid.set(Coord::new(-5, 2), 'a');
+        grid.set(Coord::new(1_000_000, 0), 'b');
+        assert_eq!(grid.get(&Coord::new(-5, 2)), Some(&'a'));
+        assert_eq!(grid.get(&Coord::new(0, 0)), None);
+        assert_eq!(grid.iter().count(), 2);
+        assert_eq!((grid.width(), grid.height()), (1_000_006, 3));
+    }
 }
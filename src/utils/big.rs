@@ -0,0 +1,230 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul};
+use std::str::FromStr;
+
+/// An unsigned integer of any size, for answers that don't fit in a `u64`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first, with no trailing zeroes
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The value as a `u64`, if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low as u64),
+            [low, high] => Some((high as u64) << 32 | low as u64),
+            _ => None,
+        }
+    }
+
+    fn trim(mut self) -> BigUint {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// Multiplies in place by `factor` and adds `addend`.
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in self.limbs.iter_mut() {
+            let value = *limb as u64 * factor as u64 + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        *self = std::mem::take(self).trim();
+    }
+
+    /// Divides in place by a small number, returning the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = remainder << 32 | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        *self = std::mem::take(self).trim();
+        remainder as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> BigUint {
+        BigUint {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        }
+        .trim()
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry = 0u64;
+        for (index, limb) in self.limbs.iter_mut().enumerate() {
+            let value = *limb as u64 + *other.limbs.get(index).unwrap_or(&0) as u64 + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut sum = self.clone();
+        sum += other;
+        sum
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: BigUint) -> BigUint {
+        self += &other;
+        self
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let value = limbs[i + j] as u64 + *a as u64 * *b as u64 + carry;
+                limbs[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint { limbs }.trim()
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> BigUint {
+        iter.fold(BigUint::zero(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> BigUint {
+        iter.fold(BigUint::zero(), |mut sum, value| {
+            sum += value;
+            sum
+        })
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The largest power of ten that fits in a limb, so decimal digits come out nine at a time.
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut chunks = vec![];
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(DECIMAL_CHUNK));
+        }
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits += &format!("{:09}", chunk);
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<BigUint, String> {
+        if value.is_empty() {
+            return Err("no digits".to_string());
+        }
+        let mut number = BigUint::zero();
+        for letter in value.chars() {
+            let digit = letter
+                .to_digit(10)
+                .ok_or_else(|| format!("'{}' is not a digit", letter))?;
+            number.mul_add_small(10, digit);
+        }
+        Ok(number)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let max = BigUint::from(u64::MAX);
+        let cases = vec![
+            (&max + &BigUint::from(1), "18446744073709551616"),
+            (&max * &max, "340282366920938463426481119284349108225"),
+            (BigUint::from(0) * max.clone(), "0"),
+            (BigUint::from(1_000_000_007), "1000000007"),
+            (
+                [max.clone(), max.clone(), max.clone()].into_iter().sum(),
+                "55340232221128654845",
+            ),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(value.to_string(), expected);
+            assert_eq!(expected.parse::<BigUint>(), Ok(value));
+        }
+        assert_eq!(max.to_u64(), Some(u64::MAX));
+        assert_eq!((&max + &max).to_u64(), None);
+        assert!(&max + &BigUint::from(1) > max);
+        assert!("12a".parse::<BigUint>().is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::error::{Error, Result};
+use std::fmt::Display;
+
+/// Integer operations that fail with [`Error::Overflow`] when built with the `checked` feature.
+/// Without it they behave like the plain operators, which panic on overflow in debug builds and
+/// wrap in release builds.
+pub trait TryArithmetic: Sized + Copy + Display {
+    fn try_add(self, other: Self) -> Result<Self>;
+    fn try_sub(self, other: Self) -> Result<Self>;
+    fn try_mul(self, other: Self) -> Result<Self>;
+}
+
+#[cfg_attr(not(feature = "checked"), allow(dead_code))]
+fn overflow(left: impl Display, operator: &str, right: impl Display) -> Error {
+    Error::Overflow(format!("{} {} {}", left, operator, right))
+}
+
+macro_rules! try_arithmetic {
+    ($($number:ty),*) => {
+        $(
+            impl TryArithmetic for $number {
+                #[cfg(feature = "checked")]
+                fn try_add(self, other: Self) -> Result<Self> {
+                    self.checked_add(other).ok_or_else(|| overflow(self, "+", other))
+                }
+
+                #[cfg(feature = "checked")]
+                fn try_sub(self, other: Self) -> Result<Self> {
+                    self.checked_sub(other).ok_or_else(|| overflow(self, "-", other))
+                }
+
+                #[cfg(feature = "checked")]
+                fn try_mul(self, other: Self) -> Result<Self> {
+                    self.checked_mul(other).ok_or_else(|| overflow(self, "*", other))
+                }
+
+                #[cfg(not(feature = "checked"))]
+                fn try_add(self, other: Self) -> Result<Self> {
+                    Ok(self + other)
+                }
+
+                #[cfg(not(feature = "checked"))]
+                fn try_sub(self, other: Self) -> Result<Self> {
+                    Ok(self - other)
+                }
+
+                #[cfg(not(feature = "checked"))]
+                fn try_mul(self, other: Self) -> Result<Self> {
+                    Ok(self * other)
+                }
+            }
+        )*
+    };
+}
+
+try_arithmetic!(i32, i64, u32, u64, usize);
+
+/// Adds up an iterator of numbers with [`TryArithmetic::try_add`].
+pub trait TrySum<T> {
+    fn try_sum(self) -> Result<T>;
+}
+
+impl<T, I> TrySum<T> for I
+where
+    T: TryArithmetic + Default,
+    I: Iterator<Item = T>,
+{
+    fn try_sum(mut self) -> Result<T> {
+        self.try_fold(T::default(), T::try_add)
+    }
+}
+
+/// The digits of `right` written after those of `left`, or `None` if that doesn't fit.
+pub fn concat(left: u64, right: u64) -> Option<u64> {
+    let mut shift = 10u64;
+    while shift <= right {
+        shift = shift.checked_mul(10)?;
+    }
+    left.checked_mul(shift)?.checked_add(right)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_try_arithmetic() {
+        assert_eq!(2u64.try_add(3), Ok(5));
+        assert_eq!(2i32.try_sub(3), Ok(-1));
+        assert_eq!(6usize.try_mul(7), Ok(42));
+        assert_eq!([1u32, 2, 3].into_iter().try_sum(), Ok(6));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            u64::MAX.try_add(1),
+            Err(Error::Overflow(format!("{} + 1", u64::MAX)))
+        );
+        assert!(0u32.try_sub(1).is_err());
+        assert!(i32::MAX.try_mul(2).is_err());
+        assert!([u64::MAX, 1].into_iter().try_sum().is_err());
+    }
+
+    #[test]
+    fn test_concat() {
+        let cases = vec![
+            (12, 345, Some(12345)),
+            (1, 0, Some(10)),
+            (15, 10, Some(1510)),
+            (7, 9, Some(79)),
+            (u64::MAX / 10, 9, None),
+            (1, u64::MAX, None),
+        ];
+        for (left, right, expected) in cases {
+            assert_eq!(concat(left, right), expected, "{} || {}", left, right);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use crate::utils::math::extended_gcd;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An exact fraction, always stored in lowest terms with a positive denominator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational::integer(0);
+    pub const ONE: Rational = Rational::integer(1);
+
+    /// Panics if `denominator` is zero.
+    pub fn new(numerator: i128, denominator: i128) -> Rational {
+        assert_ne!(denominator, 0, "a fraction can't have a zero denominator");
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Rational {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub const fn integer(value: i128) -> Rational {
+        Rational {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    /// The value as an integer, if it is one.
+    pub fn to_integer(self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Rational {
+        Rational::integer(value as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        Rational::new(
+            self.numerator * other.denominator + other.numerator * self.denominator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        Rational::new(
+            self.numerator * other.numerator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// Panics when dividing by zero.
+    fn div(self, other: Rational) -> Rational {
+        Rational::new(
+            self.numerator * other.denominator,
+            self.denominator * other.numerator,
+        )
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    extended_gcd(a, b).0
+}
+
+/// The outcome of solving a system of linear equations.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Solution {
+    /// Exactly one solution
+    Unique(Vec<Rational>),
+    /// Infinitely many solutions, given as the one with every free variable set to zero, along
+    /// with the indices of the free variables
+    Many {
+        particular: Vec<Rational>,
+        free: Vec<usize>,
+    },
+    /// The equations contradict each other
+    None,
+}
+
+/// Solves `matrix * x == rhs` exactly by Gaussian elimination. `matrix` has one row per
+/// equation and one column per variable.
+pub fn solve(matrix: &[Vec<Rational>], rhs: &[Rational]) -> Solution {
+    let variables = matrix.first().map_or(0, |row| row.len());
+    let mut rows: Vec<Vec<Rational>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, value)| row.iter().copied().chain([*value]).collect())
+        .collect();
+
+    // Reduce to row echelon form, remembering which column each pivot is in
+    let mut pivots = vec![];
+    for column in 0..variables {
+        let row = pivots.len();
+        let Some(found) = (row..rows.len()).find(|index| !rows[*index][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(row, found);
+        let pivot = rows[row][column];
+        for value in rows[row].iter_mut() {
+            *value = *value / pivot;
+        }
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            let factor = values[column];
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot_value) in values.iter_mut().zip(&pivot_row).skip(column) {
+                *value = *value - factor * *pivot_value;
+            }
+        }
+        pivots.push(column);
+    }
+
+    // A leftover row of zeroes with a non-zero right hand side can't be satisfied
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[variables].is_zero())
+    {
+        return Solution::None;
+    }
+    let mut particular = vec![Rational::ZERO; variables];
+    for (row, column) in pivots.iter().enumerate() {
+        particular[*column] = rows[row][variables];
+    }
+    match pivots.len() == variables {
+        true => Solution::Unique(particular),
+        false => Solution::Many {
+            particular,
+            free: (0..variables)
+                .filter(|column| !pivots.contains(column))
+                .collect(),
+        },
+    }
+}
+
+/// The determinant of a square matrix.
+pub fn determinant(matrix: &[Vec<Rational>]) -> Rational {
+    let mut rows = matrix.to_vec();
+    let mut result = Rational::ONE;
+    for column in 0..rows.len() {
+        let Some(found) = (column..rows.len()).find(|index| !rows[*index][column].is_zero()) else {
+            return Rational::ZERO;
+        };
+        if found != column {
+            rows.swap(column, found);
+            result = -result;
+        }
+        let pivot = rows[column][column];
+        result = result * pivot;
+        let pivot_row = rows[column].clone();
+        for values in rows.iter_mut().skip(column + 1) {
+            let factor = values[column] / pivot;
+            for (value, pivot_value) in values.iter_mut().zip(&pivot_row).skip(column) {
+                *value = *value - factor * *pivot_value;
+            }
+        }
+    }
+    result
+}
+
+/// Solves a square system `matrix * x == rhs` by Cramer's rule, or returns `None` if its
+/// determinant is zero and there isn't a single solution.
+pub fn cramer(matrix: &[Vec<Rational>], rhs: &[Rational]) -> Option<Vec<Rational>> {
+    let denominator = determinant(matrix);
+    if denominator.is_zero() {
+        return None;
+    }
+    let solution = (0..matrix.len())
+        .map(|column| {
+            let replaced: Vec<Vec<Rational>> = matrix
+                .iter()
+                .zip(rhs)
+                .map(|(row, value)| {
+                    let mut row = row.clone();
+                    row[column] = *value;
+                    row
+                })
+                .collect();
+            determinant(&replaced) / denominator
+        })
+        .collect();
+    Some(solution)
+}
+
+/// Every integer solution of `a * x + b * y == c`, which are `x + k * step_x` and
+/// `y - k * step_y` for any integer `k`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Diophantine {
+    pub x: i128,
+    pub y: i128,
+    pub step_x: i128,
+    pub step_y: i128,
+}
+
+/// Solves `a * x + b * y == c` over the integers, returning `None` if there are no solutions.
+/// `a` and `b` must not both be zero.
+pub fn diophantine(a: i128, b: i128, c: i128) -> Option<Diophantine> {
+    let (divisor, x, y) = extended_gcd(a, b);
+    if divisor == 0 || c % divisor != 0 {
+        return None;
+    }
+    let scale = c / divisor;
+    Some(Diophantine {
+        x: x * scale,
+        y: y * scale,
+        step_x: b / divisor,
+        step_y: a / divisor,
+    })
+}
+
+impl Diophantine {
+    /// The solution for a particular `k`.
+    pub fn at(&self, k: i128) -> (i128, i128) {
+        (self.x + k * self.step_x, self.y - k * self.step_y)
+    }
+
+    /// The smallest and largest `k` giving a solution where neither `x` nor `y` is negative,
+    /// using `i128::MIN` and `i128::MAX` where there's no limit. `None` if there aren't any.
+    pub fn non_negative(&self) -> Option<(i128, i128)> {
+        let (mut low, mut high) = (i128::MIN, i128::MAX);
+        // Each of `start + k * step >= 0` limits k on one side
+        for (start, step) in [(self.x, self.step_x), (self.y, -self.step_y)] {
+            match step.cmp(&0) {
+                Ordering::Greater => low = low.max(div_ceil(-start, step)),
+                Ordering::Less => high = high.min(div_floor(start, -step)),
+                Ordering::Equal if start < 0 => return None,
+                Ordering::Equal => (),
+            }
+        }
+        (low <= high).then_some((low, high))
+    }
+}
+
+fn div_floor(a: i128, b: i128) -> i128 {
+    let quotient = a / b;
+    match (a % b != 0) && ((a < 0) != (b < 0)) {
+        true => quotient - 1,
+        false => quotient,
+    }
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -div_floor(-a, b)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    fn matrix(rows: &[&[i64]]) -> Vec<Vec<Rational>> {
+        rows.iter()
+            .map(|row| row.iter().map(|value| Rational::from(*value)).collect())
+            .collect()
+    }
+
+    fn vector(values: &[i64]) -> Vec<Rational> {
+        values.iter().map(|value| Rational::from(*value)).collect()
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, 4);
+        assert_eq!(half, Rational::new(-1, -2));
+        assert_eq!((half.numerator, half.denominator), (1, 2));
+        assert_eq!(half + Rational::new(1, 3), Rational::new(5, 6));
+        assert_eq!(half - Rational::ONE, Rational::new(-1, 2));
+        assert_eq!(half * Rational::integer(4), Rational::integer(2));
+        assert_eq!(half / Rational::new(1, 4), Rational::integer(2));
+        assert_eq!(Rational::new(3, -6).to_string(), "-1/2");
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+        assert_eq!(half.to_integer(), None);
+        assert!(Rational::new(1, 3) < half);
+    }
+
+    #[test]
+    fn test_solve() {
+        let cases = vec![
+            (
+                matrix(&[&[2, 1, -1], &[-3, -1, 2], &[-2, 1, 2]]),
+                vector(&[8, -11, -3]),
+                Solution::Unique(vector(&[2, 3, -1])),
+            ),
+            (
+                matrix(&[&[1, 2], &[2, 4]]),
+                vector(&[3, 6]),
+                Solution::Many {
+                    particular: vector(&[3, 0]),
+                    free: vec![1],
+                },
+            ),
+            (matrix(&[&[1, 2], &[2, 4]]), vector(&[3, 7]), Solution::None),
+            (
+                matrix(&[&[3, 0], &[0, 4]]),
+                vector(&[1, 1]),
+                Solution::Unique(vec![Rational::new(1, 3), Rational::new(1, 4)]),
+            ),
+        ];
+        for (matrix, rhs, expected) in cases {
+            assert_eq!(solve(&matrix, &rhs), expected);
+        }
+    }
+
+    #[test]
+    fn test_cramer() {
+        let system = matrix(&[&[94, 22], &[34, 67]]);
+        assert_eq!(determinant(&system), Rational::integer(94 * 67 - 22 * 34));
+        assert_eq!(
+            cramer(&system, &vector(&[8400, 5400])),
+            Some(vector(&[80, 40]))
+        );
+        assert_eq!(cramer(&matrix(&[&[1, 2], &[2, 4]]), &vector(&[3, 6])), None);
+    }
+
+    #[test]
+    fn test_diophantine() {
+        assert_eq!(diophantine(4, 6, 7), None);
+        let solutions = diophantine(4, 6, 20).unwrap();
+        for k in -3..3 {
+            let (x, y) = solutions.at(k);
+            assert_eq!(4 * x + 6 * y, 20);
+        }
+        // 4x + 6y = 20 has (5, 0), (2, 2) as its only non-negative solutions
+        let (low, high) = solutions.non_negative().unwrap();
+        let mut found: Vec<(i128, i128)> = (low..=high).map(|k| solutions.at(k)).collect();
+        found.sort();
+        assert_eq!(found, vec![(2, 2), (5, 0)]);
+        assert_eq!(diophantine(3, 5, -1).unwrap().non_negative(), None);
+        assert_eq!(
+            diophantine(0, 5, 10).unwrap().non_negative(),
+            Some((0, i128::MAX))
+        );
+    }
+}
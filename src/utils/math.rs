@@ -0,0 +1,181 @@
+/// The greatest common divisor, with `gcd(0, 0) == 0`.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+/// The least common multiple, with `lcm(0, n) == 0`.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    match gcd(a, b) {
+        0 => 0,
+        divisor => a / divisor * b,
+    }
+}
+
+/// The greatest common divisor `g` of `a` and `b`, which is never negative, along with `x` and
+/// `y` such that `a * x + b * y == g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut previous, mut current) = ((a, 1, 0), (b, 0, 1));
+    while current.0 != 0 {
+        let quotient = previous.0 / current.0;
+        let next = (
+            previous.0 - quotient * current.0,
+            previous.1 - quotient * current.1,
+            previous.2 - quotient * current.2,
+        );
+        previous = current;
+        current = next;
+    }
+    match previous.0 < 0 {
+        true => (-previous.0, -previous.1, -previous.2),
+        false => previous,
+    }
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1`, if `a` and `modulus` are coprime.
+#[allow(dead_code, reason = "for modular division outside of `crt`")]
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (divisor, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (divisor == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// `base` to the power `exponent`, modulo `modulus`, by repeated squaring.
+#[allow(dead_code, reason = "for powers too big to work out directly")]
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let modulus = modulus as u128;
+    let (mut base, mut result) = (base as u128 % modulus, 1 % modulus);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair at once by the Chinese Remainder Theorem,
+/// returning the smallest non-negative `x` and the modulus it repeats with. The moduli needn't
+/// be coprime, but `None` is returned if the congruences contradict each other.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences.iter().try_fold(
+        (0, 1),
+        |(residue, modulus), (next_residue, next_modulus)| {
+            // residue + modulus * k ≡ next_residue, so modulus * k ≡ difference
+            let (divisor, inverse, _) = extended_gcd(modulus, *next_modulus);
+            let difference = next_residue - residue;
+            if difference % divisor != 0 {
+                return None;
+            }
+            let step = next_modulus / divisor;
+            let k = (difference / divisor * inverse).rem_euclid(step);
+            let combined = modulus * step;
+            Some(((residue + modulus * k).rem_euclid(combined), combined))
+        },
+    )
+}
+
+const POWERS_OF_TEN: [u64; 20] = {
+    let mut powers = [1; 20];
+    let mut index = 1;
+    while index < 20 {
+        powers[index] = powers[index - 1] * 10;
+        index += 1;
+    }
+    powers
+};
+
+/// The number of decimal digits in `n`, counting zero as one digit.
+pub fn count_digits(n: u64) -> u32 {
+    POWERS_OF_TEN[1..]
+        .iter()
+        .position(|power| n < *power)
+        .map_or(20, |index| index as u32 + 1)
+}
+
+/// Splits `n` into the digits before its last `low` digits and those last digits, so
+/// `split_digits(123456, 2) == (1234, 56)`.
+pub fn split_digits(n: u64, low: u32) -> (u64, u64) {
+    match POWERS_OF_TEN.get(low as usize) {
+        Some(power) => (n / power, n % power),
+        None => (0, n),
+    }
+}
+
+/// The decimal digits of `n`, most significant first.
+#[allow(dead_code, reason = "for reading digits without a `String`")]
+pub fn digits(n: u64) -> impl Iterator<Item = u8> {
+    (0..count_digits(n))
+        .rev()
+        .map(move |place| (n / POWERS_OF_TEN[place as usize] % 10) as u8)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        let cases = vec![
+            (12, 18, 6, 36),
+            (7, 5, 1, 35),
+            (0, 9, 9, 0),
+            (101, 103, 1, 10403),
+        ];
+        for (a, b, divisor, multiple) in cases {
+            assert_eq!(gcd(a, b), divisor);
+            assert_eq!(lcm(a, b), multiple);
+        }
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let cases = vec![(240, 46), (-12, 18), (0, 5), (7, 0), (17, 5)];
+        for (a, b) in cases {
+            let (divisor, x, y) = extended_gcd(a, b);
+            assert_eq!(a * x + b * y, divisor, "{} {}", a, b);
+            assert!(divisor >= 0);
+            assert!(a % divisor.max(1) == 0 && b % divisor.max(1) == 0);
+        }
+        assert_eq!(extended_gcd(240, 46).0, 2);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 7), 1);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        let cases = vec![
+            (vec![(2, 3), (3, 5), (2, 7)], Some((23, 105))),
+            (vec![(1, 4), (3, 6)], Some((9, 12))),
+            (vec![(1, 4), (2, 6)], None),
+            (vec![(50, 101), (70, 103)], Some((9443, 10403))),
+            (vec![], Some((0, 1))),
+        ];
+        for (congruences, expected) in cases {
+            assert_eq!(crt(&congruences), expected, "{:?}", congruences);
+        }
+    }
+
+    #[test]
+    fn test_digits() {
+        let cases = vec![(0, 1), (9, 1), (10, 2), (2024, 4), (u64::MAX, 20)];
+        for (n, expected) in cases {
+            assert_eq!(count_digits(n), expected, "{}", n);
+        }
+        assert_eq!(split_digits(123456, 2), (1234, 56));
+        assert_eq!(split_digits(1000, 2), (10, 0));
+        assert_eq!(split_digits(5, 25), (0, 5));
+        assert_eq!(digits(2024).collect::<Vec<_>>(), vec![2, 0, 2, 4]);
+        assert_eq!(digits(0).collect::<Vec<_>>(), vec![0]);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::config::Config;
+use rayon::prelude::*;
+
+/// Sizes the pool that parallel work runs on, from `threads` or else the config's `threads`.
+/// Rayon's default of one thread per core is left alone when neither is set.
+pub fn configure_threads(threads: Option<usize>) -> Result<(), String> {
+    let Some(threads) = threads.or(Config::global().threads) else {
+        return Ok(());
+    };
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|err| format!("Couldn't start {} threads: {}", threads, err))
+}
+
+/// Applies `f` to every item on the pool, keeping the results in the items' order.
+pub fn par_map<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    items.par_iter().map(f).collect()
+}
+
+/// The number of items that `predicate` holds for, checking them on the pool.
+pub fn par_count<T, F>(items: &[T], predicate: F) -> usize
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync + Send,
+{
+    items.par_iter().filter(|item| predicate(item)).count()
+}
+
+/// The sum of `f` over every item, worked out on the pool.
+#[allow(dead_code, reason = "the summing counterpart of `par_count`")]
+pub fn par_sum<T, F>(items: &[T], f: F) -> u64
+where
+    T: Sync,
+    F: Fn(&T) -> u64 + Sync + Send,
+{
+    items.par_iter().map(f).sum()
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_helpers() {
+        let items: Vec<u64> = (1..=1000).collect();
+        let squares = par_map(&items, |item| item * item);
+        assert_eq!(&squares[..4], &[1, 4, 9, 16]);
+        assert_eq!(squares.len(), 1000);
+        assert_eq!(par_count(&items, |item| item % 3 == 0), 333);
+        assert_eq!(par_sum(&items, |item| *item), 500_500);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::error::{parse_token, Error, Result};
+use crate::utils::{Grid, ParsedGrid};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A `&'static Regex` for a literal pattern, compiled the first time it's used.
+macro_rules! regex {
+    ($pattern:expr) => {{
+        static REGEX: std::sync::LazyLock<::regex::Regex> =
+            std::sync::LazyLock::new(|| ::regex::Regex::new($pattern).unwrap());
+        &*REGEX
+    }};
+}
+pub(crate) use regex;
+
+/// Every signed integer in `text`, a slice of `input`, ignoring whatever is between them.
+pub fn integers<T>(input: &str, text: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    regex!(r"-?\d+")
+        .find_iter(text)
+        .map(|number| parse_token(input, number.as_str()))
+        .collect()
+}
+
+/// The whitespace-separated values in `text`, a slice of `input`.
+pub fn words<T>(input: &str, text: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.split_whitespace()
+        .map(|word| parse_token(input, word))
+        .collect()
+}
+
+/// The values in `text`, a slice of `input`, between each `separator`. Whitespace around each
+/// value is ignored.
+pub fn separated<T>(input: &str, text: &str, separator: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.split(separator)
+        .map(|value| parse_token(input, value.trim()))
+        .collect()
+}
+
+/// The single digits in `text`, a slice of `input`, with nothing between them.
+pub fn digits(input: &str, text: &str) -> Result<Vec<u32>> {
+    text.char_indices()
+        .map(|(index, letter)| {
+            letter.to_digit(10).ok_or_else(|| {
+                Error::at(
+                    input,
+                    &text[index..],
+                    format!("expected a digit, found '{}'", letter),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Turns `values` read from `text` into an array, failing at `text` with the `expected` message
+/// if there are too many or too few.
+pub fn exactly<T, const N: usize>(
+    input: &str,
+    text: &str,
+    values: Vec<T>,
+    expected: &str,
+) -> Result<[T; N]> {
+    values
+        .try_into()
+        .map_err(|_| Error::at(input, text, expected))
+}
+
+/// The parts of `input` separated by blank lines, without the blank lines themselves.
+pub fn sections(input: &str) -> Vec<&str> {
+    regex!(r"\r?\n\s*\n")
+        .split(input)
+        .filter(|section| !section.trim().is_empty())
+        .collect()
+}
+
+/// The two sections of `input` either side of a blank line. The `expected` message is reported
+/// at the end of the input if there's no blank line.
+pub fn two_sections<'a>(input: &'a str, expected: &str) -> Result<(&'a str, &'a str)> {
+    match sections(input)[..] {
+        [first, second] => Ok((first, second)),
+        [_, _, third, ..] => Err(Error::at(input, third, "expected only two sections")),
+        _ => Err(Error::at(input, &input[input.len()..], expected)),
+    }
+}
+
+/// A grid of single digits, recording where any `markers` digits are.
+pub fn digit_grid(input: &str, markers: &str) -> Result<ParsedGrid<u32>> {
+    Grid::parse(input, markers, |letter| letter.to_digit(10))
+}
+
+/// A `key: values` line, split at the first `separator`, with whitespace between the values.
+pub fn key_values<K, V>(input: &str, line: &str, separator: &str) -> Result<(K, Vec<V>)>
+where
+    K: FromStr,
+    K::Err: Display,
+    V: FromStr,
+    V::Err: Display,
+{
+    let (key, values) = line.split_once(separator).ok_or_else(|| {
+        Error::at(
+            input,
+            line,
+            format!("expected '<key>{} <values>'", separator),
+        )
+    })?;
+    let values = words(input, values)?;
+    if values.is_empty() {
+        return Err(Error::at(
+            input,
+            &line[line.len()..],
+            "expected some values",
+        ));
+    }
+    Ok((parse_token(input, key.trim())?, values))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    fn position<T>(result: Result<T>) -> Option<(usize, usize)> {
+        match result {
+            Err(Error::Parse { line, column, .. }) => Some((line, column)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_values() {
+        let input = "p=0,4 v=3,-3\n1 2  3\n4, 5,6\n1203";
+        let lines: Vec<&str> = input.lines().collect();
+        assert_eq!(integers::<i32>(input, lines[0]), Ok(vec![0, 4, 3, -3]));
+        assert_eq!(words::<u8>(input, lines[1]), Ok(vec![1, 2, 3]));
+        assert_eq!(separated::<u8>(input, lines[2], ","), Ok(vec![4, 5, 6]));
+        assert_eq!(digits(input, lines[3]), Ok(vec![1, 2, 0, 3]));
+        assert_eq!(
+            exactly::<u8, 3>(input, lines[1], vec![1, 2, 3], "expected three"),
+            Ok([1, 2, 3])
+        );
+
+        let cases = vec![
+            (position(integers::<u8>(input, lines[0])), Some((1, 11))),
+            (position(words::<u8>(input, lines[0])), Some((1, 1))),
+            (
+                position(separated::<u8>(input, lines[1], ",")),
+                Some((2, 1)),
+            ),
+            (position(digits(input, lines[2])), Some((3, 2))),
+            (
+                position(exactly::<u8, 2>(input, lines[3], vec![1], "expected two")),
+                Some((4, 1)),
+            ),
+        ];
+        for (found, expected) in cases {
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_sections() {
+        let cases = vec![
+            ("a\nb\n\nc\n", vec!["a\nb", "c\n"]),
+            ("a\r\n\r\nb", vec!["a", "b"]),
+            ("\na\n\n  \n\nb\n\n", vec!["\na", "b"]),
+            ("a", vec!["a"]),
+            ("", vec![]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(sections(input), expected);
+        }
+
+        assert_eq!(two_sections("a\n\nb", "expected two"), Ok(("a", "b")));
+        assert_eq!(position(two_sections("a\nb", "expected two")), Some((2, 2)));
+        assert_eq!(
+            position(two_sections("a\n\nb\n\nc", "expected two")),
+            Some((5, 1))
+        );
+    }
+
+    #[test]
+    fn test_digit_grid() {
+        let parsed = digit_grid("012\n345", "0").unwrap();
+        assert_eq!(parsed.grid.rows().nth(1), Some(&[3, 4, 5][..]));
+        assert_eq!(parsed.find('0'), Some(crate::utils::Coord::new(0, 0)));
+        assert_eq!(position(digit_grid("01\n3x", "")), Some((2, 2)));
+    }
+
+    #[test]
+    fn test_key_values() {
+        let input = "190: 10 19\n83 17 5\n7:\n3: a";
+        let lines: Vec<&str> = input.lines().collect();
+        assert_eq!(
+            key_values::<u64, u64>(input, lines[0], ":"),
+            Ok((190, vec![10, 19]))
+        );
+        let cases = vec![(lines[1], (2, 1)), (lines[2], (3, 3)), (lines[3], (4, 4))];
+        for (line, expected) in cases {
+            assert_eq!(
+                position(key_values::<u64, u64>(input, line, ":")),
+                Some(expected)
+            );
+        }
+    }
+}
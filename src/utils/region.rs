@@ -0,0 +1,188 @@
+use crate::utils::search::{bfs, grid_neighbours};
+use crate::utils::{Coord, Diagonal, Grid};
+use std::collections::HashSet;
+
+/// A connected group of cells in a grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    /// The region's cells, in the order they were found
+    pub cells: Vec<Coord>,
+    /// The number of cell edges on the region's boundary, inside and out
+    pub perimeter: usize,
+    /// The number of straight sides, which is also its number of corners
+    pub sides: usize,
+    pub top_left: Coord,
+    pub bottom_right: Coord,
+    /// The number of separate areas the region encloses
+    pub holes: usize,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// Every region of a grid, with each cell labelled by the index of its region.
+pub struct Regions {
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+/// Splits a grid into regions of orthogonally adjacent cells, where `connected` says whether two
+/// neighbouring cells belong together.
+pub fn find_regions<T>(grid: &Grid<T>, connected: impl Fn(&T, &T) -> bool) -> Regions {
+    let mut labels = Grid::filled(grid.width() as usize, grid.height() as usize, usize::MAX);
+    let mut groups = vec![];
+    for (coord, _) in grid.iter() {
+        if labels[coord] != usize::MAX {
+            continue;
+        }
+        let cells: Vec<Coord> = bfs([coord], grid_neighbours(grid, &connected))
+            .map(|(cell, _)| cell)
+            .collect();
+        for cell in &cells {
+            labels.set(*cell, groups.len());
+        }
+        groups.push(cells);
+    }
+
+    let regions = groups
+        .into_iter()
+        .enumerate()
+        .map(|(label, cells)| measure(&labels, label, cells))
+        .collect();
+    Regions { labels, regions }
+}
+
+fn measure(labels: &Grid<usize>, label: usize, cells: Vec<Coord>) -> Region {
+    let inside = |coord: &Coord| labels.get(coord) == Some(&label);
+
+    let perimeter = cells
+        .iter()
+        .flat_map(|cell| cell.neighbours4())
+        .filter(|neighbour| !inside(neighbour))
+        .count();
+
+    // A corner is either convex, with both sides outside, or concave, with both sides inside
+    // but the diagonal outside
+    let sides = cells
+        .iter()
+        .flat_map(|cell| Diagonal::ALL.map(|diagonal| (*cell, diagonal)))
+        .filter(|(cell, diagonal)| {
+            let (vertical, horizontal) = diagonal.directions();
+            match (inside(&(*cell + vertical)), inside(&(*cell + horizontal))) {
+                (false, false) => true,
+                (true, true) => !inside(&(*cell + diagonal.offset())),
+                _ => false,
+            }
+        })
+        .count();
+
+    let top_left = Coord::new(
+        cells.iter().map(|cell| cell.x).min().unwrap_or(0),
+        cells.iter().map(|cell| cell.y).min().unwrap_or(0),
+    );
+    let bottom_right = Coord::new(
+        cells.iter().map(|cell| cell.x).max().unwrap_or(0),
+        cells.iter().map(|cell| cell.y).max().unwrap_or(0),
+    );
+
+    Region {
+        holes: count_holes(&inside, top_left, bottom_right),
+        cells,
+        perimeter,
+        sides,
+        top_left,
+        bottom_right,
+    }
+}
+
+/// Counts the areas outside a region that can't reach past its bounding box.
+fn count_holes(inside: &impl Fn(&Coord) -> bool, top_left: Coord, bottom_right: Coord) -> usize {
+    let (frame_top_left, frame_bottom_right) = (top_left.plus(-1, -1), bottom_right.plus(1, 1));
+    let within = |coord: &Coord, top_left: Coord, bottom_right: Coord| {
+        (top_left.x..=bottom_right.x).contains(&coord.x)
+            && (top_left.y..=bottom_right.y).contains(&coord.y)
+    };
+    let outside_neighbours = |top_left: Coord, bottom_right: Coord| {
+        move |coord: &Coord| {
+            coord
+                .neighbours4()
+                .into_iter()
+                .filter(|next| within(next, top_left, bottom_right) && !inside(next))
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let mut seen: HashSet<Coord> = bfs(
+        [frame_top_left],
+        outside_neighbours(frame_top_left, frame_bottom_right),
+    )
+    .map(|(coord, _)| coord)
+    .collect();
+
+    let mut holes = 0;
+    for y in top_left.y..=bottom_right.y {
+        for x in top_left.x..=bottom_right.x {
+            let coord = Coord::new(x, y);
+            if inside(&coord) || seen.contains(&coord) {
+                continue;
+            }
+            holes += 1;
+            seen.extend(
+                bfs([coord], outside_neighbours(top_left, bottom_right)).map(|(coord, _)| coord),
+            );
+        }
+    }
+    holes
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_regions() {
+        let grid = Grid::parse("AAAA\nBBCD\nBBCC\nEEEC\n", "", Some)
+            .unwrap()
+            .grid;
+        let found = find_regions(&grid, |a, b| a == b);
+        let summary: Vec<(char, usize, usize, usize)> = found
+            .regions
+            .iter()
+            .map(|region| {
+                (
+                    grid[region.cells[0]],
+                    region.area(),
+                    region.perimeter,
+                    region.sides,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4)
+            ]
+        );
+        assert_eq!(found.labels[Coord::new(3, 3)], 2);
+        assert_eq!(found.regions[2].top_left, Coord::new(2, 1));
+        assert_eq!(found.regions[2].bottom_right, Coord::new(3, 3));
+    }
+
+    #[test]
+    fn test_holes() {
+        let grid = Grid::parse("OOOOO\nOXOXO\nOOOOO\nOXXOO\nOOOOO\n", "", Some)
+            .unwrap()
+            .grid;
+        let found = find_regions(&grid, |a, b| a == b);
+        assert_eq!(found.regions[0].holes, 3);
+        assert_eq!(found.regions[0].sides, 4 + 4 + 4 + 4);
+        assert!(found.regions[1..].iter().all(|region| region.holes == 0));
+    }
+}
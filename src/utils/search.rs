@@ -0,0 +1,329 @@
+use crate::utils::{Coord, Grid};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// A breadth-first search, yielding each reachable node with its distance from the nearest start.
+pub struct Bfs<N, F> {
+    queue: VecDeque<(N, usize)>,
+    parents: HashMap<N, Option<N>>,
+    neighbours: F,
+}
+
+pub fn bfs<N, F, I>(starts: impl IntoIterator<Item = N>, neighbours: F) -> Bfs<N, F>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if parents.insert(start.clone(), None).is_none() {
+            queue.push_back((start, 0));
+        }
+    }
+    Bfs {
+        queue,
+        parents,
+        neighbours,
+    }
+}
+
+impl<N, F, I> Iterator for Bfs<N, F>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Item = (N, usize);
+
+    fn next(&mut self) -> Option<(N, usize)> {
+        let (node, distance) = self.queue.pop_front()?;
+        for next in (self.neighbours)(&node) {
+            if let Entry::Vacant(entry) = self.parents.entry(next.clone()) {
+                entry.insert(Some(node.clone()));
+                self.queue.push_back((next, distance + 1));
+            }
+        }
+        Some((node, distance))
+    }
+}
+
+impl<N, F> Bfs<N, F>
+where
+    N: Eq + Hash + Clone,
+{
+    /// A shortest path from a start to `goal`, once `goal` has been reached.
+    #[allow(dead_code, reason = "the days so far only need distances")]
+    pub fn path_to(&self, goal: &N) -> Option<Vec<N>> {
+        let mut path = vec![goal.clone()];
+        let mut current = self.parents.get(goal)?;
+        while let Some(node) = current {
+            path.push(node.clone());
+            current = &self.parents[node];
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// A depth-first search, yielding each reachable node the first time it's seen.
+pub struct Dfs<N, F> {
+    stack: Vec<N>,
+    seen: HashSet<N>,
+    neighbours: F,
+}
+
+pub fn dfs<N, F, I>(start: N, neighbours: F) -> Dfs<N, F>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    Dfs {
+        stack: vec![start],
+        seen: HashSet::new(),
+        neighbours,
+    }
+}
+
+impl<N, F, I> Iterator for Dfs<N, F>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        loop {
+            let node = self.stack.pop()?;
+            if !self.seen.insert(node.clone()) {
+                continue;
+            }
+            let next = (self.neighbours)(&node)
+                .into_iter()
+                .filter(|next| !self.seen.contains(next));
+            self.stack.extend(next);
+            return Some(node);
+        }
+    }
+}
+
+/// The cheapest path from `start` to a goal, with its cost, where `neighbours` gives each next
+/// node with the cost of stepping to it.
+#[allow(dead_code, reason = "no day has a weighted maze yet")]
+pub fn dijkstra<N, F, I, G>(start: N, neighbours: F, is_goal: G) -> Option<(Vec<N>, u64)>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+/// Like [`dijkstra`], guided by a `heuristic` that must never overestimate the remaining cost.
+#[allow(dead_code, reason = "no day has a weighted maze yet")]
+pub fn astar<N, F, I, H, G>(
+    start: N,
+    mut neighbours: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(Vec<N>, u64)>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    // Nodes live in `nodes` so the heap only has to order numbers
+    let mut nodes = vec![start.clone()];
+    let mut best: HashMap<N, (u64, Option<usize>)> = HashMap::from([(start.clone(), (0, None))]);
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        if best.get(&node).is_some_and(|(known, _)| *known < cost) {
+            continue;
+        }
+        if is_goal(&node) {
+            let mut path = vec![node.clone()];
+            let mut parent = best[&node].1;
+            while let Some(index) = parent {
+                path.push(nodes[index].clone());
+                parent = best[&nodes[index]].1;
+            }
+            path.reverse();
+            return Some((path, cost));
+        }
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if best.get(&next).is_none_or(|(known, _)| next_cost < *known) {
+                best.insert(next.clone(), (next_cost, Some(index)));
+                heap.push(Reverse((
+                    next_cost + heuristic(&next),
+                    next_cost,
+                    nodes.len(),
+                )));
+                nodes.push(next);
+            }
+        }
+    }
+    None
+}
+
+/// The distance to every node reachable from `start`, and how many shortest paths lead there.
+#[allow(dead_code, reason = "no day counts paths by length yet")]
+pub fn count_shortest_paths<N, F, I>(start: N, mut neighbours: F) -> HashMap<N, (usize, u64)>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut counts = HashMap::from([(start.clone(), (0, 1))]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let (distance, paths) = counts[&node];
+        for next in neighbours(&node) {
+            match counts.entry(next.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert((distance + 1, paths));
+                    queue.push_back(next);
+                }
+                Entry::Occupied(mut entry) if entry.get().0 == distance + 1 => {
+                    entry.get_mut().1 += paths;
+                }
+                Entry::Occupied(_) => (),
+            }
+        }
+    }
+    counts
+}
+
+/// The number of distinct paths from `start` to any goal. The graph must be acyclic.
+pub fn count_paths<N, F, I, G>(start: N, mut neighbours: F, mut is_goal: G) -> u64
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut counts: HashMap<N, u64> = HashMap::new();
+    // Each node is visited twice: once to queue its neighbours and once to add them up
+    let mut stack = vec![(start.clone(), false)];
+    while let Some((node, expanded)) = stack.pop() {
+        if counts.contains_key(&node) {
+            continue;
+        }
+        if is_goal(&node) {
+            counts.insert(node, 1);
+            continue;
+        }
+        let next: Vec<N> = neighbours(&node).into_iter().collect();
+        if expanded {
+            let total = next.iter().filter_map(|next| counts.get(next)).sum();
+            counts.insert(node, total);
+        } else {
+            stack.push((node, true));
+            stack.extend(
+                next.into_iter()
+                    .filter(|next| !counts.contains_key(next))
+                    .map(|next| (next, false)),
+            );
+        }
+    }
+    counts[&start]
+}
+
+/// A neighbour function over a grid's orthogonal steps, allowing a step from one cell to the
+/// next when `allowed` says so.
+pub fn grid_neighbours<'a, T>(
+    grid: &'a Grid<T>,
+    allowed: impl Fn(&T, &T) -> bool + 'a,
+) -> impl FnMut(&Coord) -> Vec<Coord> + 'a {
+    move |coord| {
+        let Some(from) = grid.get(coord) else {
+            return vec![];
+        };
+        coord
+            .neighbours4()
+            .into_iter()
+            .filter(|next| grid.get(next).is_some_and(|to| allowed(from, to)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    fn maze() -> Grid<char> {
+        Grid::parse("S.#\n#..\n..E\n", "", Some).unwrap().grid
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let mut search = bfs(
+            [Coord::new(0, 0)],
+            grid_neighbours(&grid, |_, to| *to != '#'),
+        );
+        let goal = Coord::new(2, 2);
+        assert_eq!(search.find(|(node, _)| *node == goal), Some((goal, 4)));
+        assert_eq!(search.path_to(&goal).map(|path| path.len()), Some(5));
+        assert_eq!(search.path_to(&Coord::new(2, 0)), None);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let grid = maze();
+        let reachable = dfs(Coord::new(0, 0), grid_neighbours(&grid, |_, to| *to != '#'));
+        assert_eq!(reachable.count(), 7);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // Stepping onto a higher number costs that number
+        let grid = Grid::from_rows(vec![vec![1, 9, 1], vec![1, 1, 1]]).unwrap();
+        let goal = Coord::new(2, 0);
+        let neighbours = |coord: &Coord| {
+            coord
+                .neighbours4()
+                .into_iter()
+                .filter_map(|next| grid.get(&next).map(|cost| (next, *cost as u64)))
+                .collect::<Vec<_>>()
+        };
+        let (path, cost) = dijkstra(Coord::new(0, 0), neighbours, |node| *node == goal).unwrap();
+        assert_eq!(cost, 4);
+        assert_eq!(path.len(), 5);
+        let (_, cost) = astar(
+            Coord::new(0, 0),
+            neighbours,
+            |node| node.manhattan(&goal) as u64,
+            |node| *node == goal,
+        )
+        .unwrap();
+        assert_eq!(cost, 4);
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let grid = Grid::from_rows(vec![vec![0, 1, 2], vec![1, 2, 3], vec![2, 3, 4]]).unwrap();
+        let uphill = |from: &u32, to: &u32| *to == from + 1;
+        assert_eq!(
+            count_paths(
+                Coord::new(0, 0),
+                grid_neighbours(&grid, uphill),
+                |node| grid[*node] == 4
+            ),
+            6
+        );
+        let counts = count_shortest_paths(Coord::new(0, 0), grid_neighbours(&grid, uphill));
+        assert_eq!(counts[&Coord::new(2, 2)], (4, 6));
+        assert_eq!(counts[&Coord::new(1, 1)], (2, 2));
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A state that changes one step at a time, counting the steps taken.
+pub struct Simulation<S, F> {
+    state: S,
+    step: F,
+    steps: u64,
+}
+
+pub fn simulate<S, F>(start: S, step: F) -> Simulation<S, F>
+where
+    F: FnMut(&S) -> S,
+{
+    Simulation {
+        state: start,
+        step,
+        steps: 0,
+    }
+}
+
+/// Where a simulation starts repeating itself, and how often it repeats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first step whose state comes round again
+    pub start: u64,
+    pub length: u64,
+}
+
+impl Cycle {
+    /// The step before the cycle came round that has the same state as `step`.
+    pub fn equivalent(&self, step: u64) -> u64 {
+        match step < self.start {
+            true => step,
+            false => self.start + (step - self.start) % self.length,
+        }
+    }
+}
+
+impl<S, F> Simulation<S, F>
+where
+    F: FnMut(&S) -> S,
+{
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// The number of steps taken so far.
+    #[allow(dead_code, reason = "for saying how far a run got")]
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    fn advance(&mut self) {
+        self.state = (self.step)(&self.state);
+        self.steps += 1;
+    }
+
+    /// Takes `count` steps, returning the state at the end.
+    pub fn run(&mut self, count: u64) -> &S {
+        for _ in 0..count {
+            self.advance();
+        }
+        &self.state
+    }
+
+    /// Steps until `done` holds for the state, checking the current state first, returning how
+    /// many steps have been taken by then. Gives up after `limit` more steps.
+    pub fn run_until(&mut self, limit: u64, mut done: impl FnMut(&S) -> bool) -> Option<u64> {
+        for _ in 0..limit {
+            if done(&self.state) {
+                return Some(self.steps);
+            }
+            self.advance();
+        }
+        done(&self.state).then_some(self.steps)
+    }
+
+    /// Finds the cycle the states fall into with Brent's algorithm, which compares states
+    /// without storing them. Leaves the simulation one cycle past its start, at the same state
+    /// as at `cycle.start`. Never returns if the states never repeat.
+    pub fn brent(&mut self) -> Cycle
+    where
+        S: Eq + Clone,
+    {
+        // Find the length by racing ahead, resetting the tortoise at each power of two
+        let start = self.state.clone();
+        let (mut power, mut length) = (1, 1);
+        let mut tortoise = start.clone();
+        let mut hare = (self.step)(&start);
+        while tortoise != hare {
+            if power == length {
+                tortoise = hare.clone();
+                power *= 2;
+                length = 0;
+            }
+            hare = (self.step)(&hare);
+            length += 1;
+        }
+
+        // Then find the start by walking two states a cycle apart until they meet
+        let mut tortoise = start;
+        let mut hare = tortoise.clone();
+        for _ in 0..length {
+            hare = (self.step)(&hare);
+        }
+        let mut offset = 0;
+        while tortoise != hare {
+            tortoise = (self.step)(&tortoise);
+            hare = (self.step)(&hare);
+            offset += 1;
+        }
+
+        let cycle = Cycle {
+            start: self.steps + offset,
+            length,
+        };
+        self.state = hare;
+        self.steps = cycle.start + length;
+        cycle
+    }
+
+    /// Finds the cycle the states fall into by remembering every state seen, leaving the
+    /// simulation one cycle past its start like [`Simulation::brent`].
+    #[allow(dead_code, reason = "for states too slow to step twice")]
+    pub fn find_cycle(&mut self) -> Cycle
+    where
+        S: Eq + Hash + Clone,
+    {
+        self.remember_until(u64::MAX)
+            .map(|(cycle, _)| cycle)
+            .expect("a simulation can't run for u64::MAX steps")
+    }
+
+    /// Moves forward to step `target`, which may be far in the future: once the states repeat,
+    /// whole cycles are skipped. Returns the cycle if one was found on the way.
+    #[allow(dead_code, reason = "for questions about step 10^15 and the like")]
+    pub fn jump_to(&mut self, target: u64) -> Option<Cycle>
+    where
+        S: Eq + Hash + Clone,
+    {
+        let (cycle, history) = self.remember_until(target)?;
+        let first = cycle.start + cycle.length - history.len() as u64;
+        self.state = history[(cycle.equivalent(target) - first) as usize].clone();
+        self.steps = target;
+        Some(cycle)
+    }
+
+    /// Steps until a state repeats, returning the cycle along with every state since this was
+    /// called, or `None` if step `target` is reached first.
+    fn remember_until(&mut self, target: u64) -> Option<(Cycle, Vec<S>)>
+    where
+        S: Eq + Hash + Clone,
+    {
+        let mut seen = HashMap::new();
+        let mut history = vec![];
+        while self.steps < target {
+            if let Some(&start) = seen.get(&self.state) {
+                let cycle = Cycle {
+                    start,
+                    length: self.steps - start,
+                };
+                return Some((cycle, history));
+            }
+            seen.insert(self.state.clone(), self.steps);
+            history.push(self.state.clone());
+            self.advance();
+        }
+        None
+    }
+}
+
+/// Yields each state in turn, starting with the current one.
+impl<S, F> Iterator for Simulation<S, F>
+where
+    S: Clone,
+    F: FnMut(&S) -> S,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        let state = self.state.clone();
+        self.advance();
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    /// Counts 0 to 9, then loops around 4 to 9.
+    fn counter(value: &u64) -> u64 {
+        match value {
+            9 => 4,
+            _ => value + 1,
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let mut simulation = simulate(0, counter);
+        assert_eq!(*simulation.run(12), 6);
+        assert_eq!(simulation.run_until(100, |value| *value == 5), Some(17));
+        assert_eq!(simulation.run_until(100, |value| *value == 5), Some(17));
+        assert_eq!(simulation.run_until(100, |value| *value == 0), None);
+        assert_eq!(
+            simulate(0, counter).take(4).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let expected = Cycle {
+            start: 4,
+            length: 6,
+        };
+        let mut simulation = simulate(0, counter);
+        assert_eq!(simulation.brent(), expected);
+        assert_eq!((simulation.steps(), *simulation.state()), (10, 4));
+        let mut simulation = simulate(0, counter);
+        assert_eq!(simulation.find_cycle(), expected);
+        assert_eq!((simulation.steps(), *simulation.state()), (10, 4));
+
+        // Starting part way through shifts the cycle too
+        let mut simulation = simulate(0, counter);
+        simulation.run(2);
+        assert_eq!(simulation.brent(), expected);
+        let mut simulation = simulate(0, counter);
+        simulation.run(7);
+        assert_eq!(simulation.find_cycle().length, 6);
+
+        assert_eq!(simulate(5, |value: &u64| *value).brent().length, 1);
+    }
+
+    #[test]
+    fn test_jump_to() {
+        let cases = vec![
+            (3, 3, None),
+            (10, 4, None),
+            (11, 5, Some(6)),
+            (1_000_000_000_000_000, 4, Some(6)),
+            (1_000_000_000_000_003, 7, Some(6)),
+        ];
+        for (target, value, length) in cases {
+            let mut simulation = simulate(0, counter);
+            let cycle = simulation.jump_to(target);
+            assert_eq!(cycle.map(|cycle| cycle.length), length, "{}", target);
+            assert_eq!((simulation.steps(), *simulation.state()), (target, value));
+        }
+    }
+}
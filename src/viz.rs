@@ -0,0 +1,313 @@
+use crate::error;
+use crate::utils::{Coord, Grid};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+use std::io::{self, stdout, IsTerminal, Stdout, Write};
+use std::time::{Duration, Instant};
+
+pub mod image;
+pub mod record;
+
+/// One character of a frame, with an optional colour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub glyph: char,
+    pub colour: Option<Color>,
+}
+
+impl Cell {
+    pub fn plain(glyph: char) -> Cell {
+        Cell {
+            glyph,
+            colour: None,
+        }
+    }
+
+    pub fn coloured(glyph: char, colour: Color) -> Cell {
+        Cell {
+            glyph,
+            colour: Some(colour),
+        }
+    }
+}
+
+/// A colour for the region or group numbered `label`, spread out so neighbouring labels differ.
+pub fn label_colour(label: usize) -> Color {
+    let hash = (label as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    // Keep each channel away from black so labels stand out against the background
+    let channel = |shift: u32| 64 + (hash >> shift) as u8 % 192;
+    Color::Rgb {
+        r: channel(40),
+        g: channel(48),
+        b: channel(56),
+    }
+}
+
+/// A picture of a puzzle at one step, with the point the viewport should keep in sight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub cells: Grid<Cell>,
+    pub focus: Option<Coord>,
+    pub caption: String,
+}
+
+impl Frame {
+    /// A frame showing each cell of a grid as `style` draws it.
+    pub fn from_grid<T>(grid: &Grid<T>, style: impl Fn(&T) -> Cell) -> Frame {
+        let rows = grid
+            .rows()
+            .map(|row| row.iter().map(&style).collect())
+            .collect();
+        Frame {
+            cells: Grid::from_rows(rows).unwrap_or(Grid::filled(0, 0, Cell::plain(' '))),
+            focus: None,
+            caption: String::new(),
+        }
+    }
+
+    pub fn focus(mut self, focus: Coord) -> Frame {
+        self.focus = Some(focus);
+        self
+    }
+
+    pub fn caption(mut self, caption: impl Into<String>) -> Frame {
+        self.caption = caption.into();
+        self
+    }
+
+    /// The frame's text, without colours.
+    pub fn text(&self) -> String {
+        self.cells
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.glyph).collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+/// Whether a visualization should keep going.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flow {
+    Continue,
+    Stop,
+}
+
+/// Somewhere frames can be drawn.
+pub trait Canvas {
+    fn draw(&mut self, frame: &Frame) -> Flow;
+}
+
+/// Days that can show their solution step by step.
+pub trait Visualize {
+    /// Solves `part`, drawing each step on `canvas`. Returns the answer, or `None` if the
+    /// canvas stopped the visualization before the end.
+    fn visualize(
+        &self,
+        input: &str,
+        part: u8,
+        canvas: &mut dyn Canvas,
+    ) -> error::Result<Option<String>>;
+}
+
+/// Plays frames in the terminal, with controls to pause, step and change speed. Does nothing
+/// when stdout isn't a terminal.
+pub struct Player {
+    stdout: Stdout,
+    delay: Duration,
+    paused: bool,
+    frames: usize,
+    started: bool,
+    error: Option<io::Error>,
+}
+
+const CONTROLS: &str = "space: pause, n: step, +/-: speed, q: quit";
+
+impl Player {
+    pub fn new(delay: Duration) -> Player {
+        Player {
+            stdout: stdout(),
+            delay,
+            paused: false,
+            frames: 0,
+            started: false,
+            error: None,
+        }
+    }
+
+    /// Restores the terminal, returning any error that stopped playback.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.restore()?;
+        self.error.take().map_or(Ok(()), Err)
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        terminal::enable_raw_mode()?;
+        self.started = true;
+        execute!(self.stdout, terminal::EnterAlternateScreen, cursor::Hide)
+    }
+
+    fn restore(&mut self) -> io::Result<()> {
+        if !self.started {
+            return Ok(());
+        }
+        self.started = false;
+        execute!(self.stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()
+    }
+
+    fn render(&mut self, frame: &Frame) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        // Leave a line for the status bar
+        let size = Coord::new(columns as i64, rows.saturating_sub(1) as i64);
+        let origin = viewport(frame, size);
+
+        write_frame(&mut self.stdout, frame, origin, size)?;
+        let status = format!(
+            "{} | frame {} | {:?}{} | {}",
+            frame.caption,
+            self.frames,
+            self.delay,
+            if self.paused { " paused" } else { "" },
+            CONTROLS
+        );
+        queue!(
+            self.stdout,
+            cursor::MoveTo(0, size.y as u16),
+            Print(status.chars().take(columns as usize).collect::<String>())
+        )?;
+        self.stdout.flush()
+    }
+
+    /// Waits out the delay between frames, or until the next step while paused, handling keys.
+    fn wait(&mut self, frame: &Frame) -> io::Result<Flow> {
+        let deadline = Instant::now() + self.delay;
+        loop {
+            if !self.paused {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if !event::poll(remaining)? {
+                    return Ok(Flow::Continue);
+                }
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Flow::Stop),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(Flow::Stop)
+                }
+                KeyCode::Char(' ') => self.paused = !self.paused,
+                KeyCode::Char('n') | KeyCode::Right if self.paused => return Ok(Flow::Continue),
+                KeyCode::Char('+') => self.delay = (self.delay / 2).max(Duration::from_millis(1)),
+                KeyCode::Char('-') => self.delay *= 2,
+                _ => continue,
+            }
+            self.render(frame)?;
+        }
+    }
+}
+
+impl Canvas for Player {
+    fn draw(&mut self, frame: &Frame) -> Flow {
+        if !self.stdout.is_terminal() || self.error.is_some() {
+            return Flow::Continue;
+        }
+        self.frames += 1;
+        let result = match self.started {
+            true => Ok(()),
+            false => self.start(),
+        }
+        .and_then(|_| self.render(frame))
+        .and_then(|_| self.wait(frame));
+        result.unwrap_or_else(|err| {
+            self.error = Some(err);
+            Flow::Stop
+        })
+    }
+}
+
+impl Drop for Player {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+/// Clears the screen and draws the part of `frame` that starts at `origin` and fits in `size`,
+/// changing colour only where it differs from the cell before.
+fn write_frame(out: &mut impl Write, frame: &Frame, origin: Coord, size: Coord) -> io::Result<()> {
+    queue!(out, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+    for y in 0..size.y.min(frame.cells.height() - origin.y) {
+        queue!(out, cursor::MoveTo(0, y as u16))?;
+        let mut current = None;
+        for x in 0..size.x.min(frame.cells.width() - origin.x) {
+            let cell = frame.cells[origin + Coord::new(x, y)];
+            if cell.colour != current {
+                match cell.colour {
+                    Some(colour) => queue!(out, SetForegroundColor(colour))?,
+                    None => queue!(out, ResetColor)?,
+                }
+                current = cell.colour;
+            }
+            queue!(out, Print(cell.glyph))?;
+        }
+        if current.is_some() {
+            queue!(out, ResetColor)?;
+        }
+    }
+    Ok(())
+}
+
+/// The top left of the part of a frame that fits in `size`, keeping its focus near the middle.
+fn viewport(frame: &Frame, size: Coord) -> Coord {
+    let focus = frame.focus.unwrap_or(Coord::ORIGIN);
+    let axis = |focus: i64, length: i64, visible: i64| {
+        (focus - visible / 2).clamp(0, (length - visible).max(0))
+    };
+    Coord::new(
+        axis(focus.x, frame.cells.width(), size.x),
+        axis(focus.y, frame.cells.height(), size.y),
+    )
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    /// A frame of plain text, for tests of what's done with frames.
+    pub fn text_frame(text: &str) -> Frame {
+        let grid = Grid::parse(text, "", Some).unwrap().grid;
+        Frame::from_grid(&grid, |letter| Cell::plain(*letter))
+    }
+
+    #[test]
+    fn test_viewport() {
+        let frame = text_frame(&".".repeat(20)).focus(Coord::new(15, 0));
+        let cases = vec![
+            (Coord::new(10, 1), Coord::new(10, 0)),
+            (Coord::new(4, 1), Coord::new(13, 0)),
+            (Coord::new(40, 5), Coord::new(0, 0)),
+        ];
+        for (size, expected) in cases {
+            assert_eq!(viewport(&frame, size), expected);
+        }
+        let frame = frame.focus(Coord::new(1, 0));
+        assert_eq!(viewport(&frame, Coord::new(4, 1)), Coord::new(0, 0));
+    }
+
+    #[test]
+    fn test_frame() {
+        let frame = text_frame("ab\ncd");
+        assert_eq!(frame.text(), "ab\ncd\n");
+        let grid = Grid::from_rows(vec![vec![1, 0], vec![0, 1]]).unwrap();
+        let frame = Frame::from_grid(&grid, |value| match value {
+            1 => Cell::coloured('#', Color::Red),
+            _ => Cell::plain('.'),
+        });
+        assert_eq!(frame.text(), "#.\n.#\n");
+        assert_eq!(frame.cells[Coord::new(0, 0)].colour, Some(Color::Red));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::utils::Grid;
+use crate::viz::{Canvas, Cell, Flow, Frame};
+use crossterm::style::Color;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A colour as red, green and blue.
+pub type Rgb = [u8; 3];
+
+const BACKGROUND: Rgb = [16, 16, 16];
+const FOREGROUND: Rgb = [224, 224, 224];
+
+/// A picture of a grid, with each cell drawn as a square of one colour.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// An image of `grid` with each cell drawn as a `scale` by `scale` square of the colour
+    /// `palette` gives its value.
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, palette: impl Fn(&T) -> Rgb) -> Image {
+        let scale = scale.max(1);
+        let (width, height) = (
+            grid.width() as usize * scale,
+            grid.height() as usize * scale,
+        );
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in grid.rows() {
+            let line: Vec<Rgb> = row
+                .iter()
+                .flat_map(|cell| [palette(cell)].repeat(scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend(&line);
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// An image of a frame, with coloured cells in their colour, blank cells dark and any other
+    /// plain cell light.
+    pub fn from_frame(frame: &Frame, scale: usize) -> Image {
+        Image::from_grid(&frame.cells, scale, |cell: &Cell| match cell.colour {
+            Some(colour) => rgb(colour),
+            None if matches!(cell.glyph, ' ' | '.') => BACKGROUND,
+            None => FOREGROUND,
+        })
+    }
+
+    /// Writes the image as a binary PPM, which needs nothing but the raw pixels.
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(self.pixels.as_flattened())
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(self.pixels.as_flattened())?;
+        Ok(writer.finish()?)
+    }
+
+    /// Saves the image as a PPM or, when built with the `png` feature, a PNG, depending on the
+    /// extension of `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let format = ImageFormat::of(path)?;
+        let mut out = BufWriter::new(File::create(path)?);
+        match format {
+            ImageFormat::Ppm => self.write_ppm(&mut out)?,
+            #[cfg(feature = "png")]
+            ImageFormat::Png => self.write_png(&mut out)?,
+        }
+        out.flush()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ImageFormat {
+    Ppm,
+    #[cfg(feature = "png")]
+    Png,
+}
+
+impl ImageFormat {
+    fn of(path: &Path) -> io::Result<ImageFormat> {
+        let unsupported = |message: String| io::Error::new(io::ErrorKind::Unsupported, message);
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ppm") => Ok(ImageFormat::Ppm),
+            #[cfg(feature = "png")]
+            Some("png") => Ok(ImageFormat::Png),
+            #[cfg(not(feature = "png"))]
+            Some("png") => Err(unsupported(
+                "Writing PNGs needs the 'png' feature, build with --features png".to_string(),
+            )),
+            _ => Err(unsupported(format!(
+                "Can't tell which image format to write for '{}'",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// The RGB value of a terminal colour, using the usual xterm palette for indexed colours.
+pub fn rgb(colour: Color) -> Rgb {
+    const BASIC: [Rgb; 16] = [
+        [0, 0, 0],
+        [128, 0, 0],
+        [0, 128, 0],
+        [128, 128, 0],
+        [0, 0, 128],
+        [128, 0, 128],
+        [0, 128, 128],
+        [192, 192, 192],
+        [128, 128, 128],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [0, 0, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+    let index = match colour {
+        Color::Reset => return FOREGROUND,
+        Color::Rgb { r, g, b } => return [r, g, b],
+        Color::AnsiValue(index) => index,
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+    };
+    match index {
+        0..=15 => BASIC[index as usize],
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let cube = index - 16;
+            [level(cube / 36), level(cube / 6 % 6), level(cube % 6)]
+        }
+        _ => [8 + (index - 232) * 10; 3],
+    }
+}
+
+/// Writes each image of a sequence to a numbered file in a directory.
+pub struct Sequence {
+    directory: PathBuf,
+    extension: &'static str,
+    scale: usize,
+    count: usize,
+    error: Option<io::Error>,
+}
+
+impl Sequence {
+    /// Writes to `directory`, creating it if needed, as PNGs if `png` is set or PPMs otherwise.
+    /// Frames drawn on the sequence have each cell scaled up to `scale` pixels square.
+    pub fn create(directory: &Path, png: bool, scale: usize) -> io::Result<Sequence> {
+        let extension = if png { "png" } else { "ppm" };
+        ImageFormat::of(Path::new(&format!("frame.{}", extension)))?;
+        fs::create_dir_all(directory)?;
+        Ok(Sequence {
+            directory: directory.to_path_buf(),
+            extension,
+            scale,
+            count: 0,
+            error: None,
+        })
+    }
+
+    /// Saves the next image of the sequence, returning where it was written.
+    pub fn push(&mut self, image: &Image) -> io::Result<PathBuf> {
+        self.count += 1;
+        let path = self
+            .directory
+            .join(format!("{:05}.{}", self.count, self.extension));
+        image.save(&path)?;
+        Ok(path)
+    }
+
+    /// The number of images written, or any error that stopped the sequence.
+    pub fn finish(mut self) -> io::Result<usize> {
+        self.error.take().map_or(Ok(self.count), Err)
+    }
+}
+
+impl Canvas for Sequence {
+    fn draw(&mut self, frame: &Frame) -> Flow {
+        if self.error.is_some() {
+            return Flow::Stop;
+        }
+        match self.push(&Image::from_frame(frame, self.scale)) {
+            Ok(_) => Flow::Continue,
+            Err(err) => {
+                self.error = Some(err);
+                Flow::Stop
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::utils::Coord;
+    use crate::viz::test::text_frame;
+    use std::env;
+
+    fn pixel(image: &Image, coord: Coord) -> Rgb {
+        image.pixels[coord.y as usize * image.width + coord.x as usize]
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let grid = Grid::from_rows(vec![vec![0, 1], vec![2, 0]]).unwrap();
+        let image = Image::from_grid(&grid, 2, |value| [*value * 100, 0, 0]);
+        assert_eq!((image.width, image.height), (4, 4));
+        let cases = vec![
+            (Coord::new(0, 0), [0, 0, 0]),
+            (Coord::new(3, 1), [100, 0, 0]),
+            (Coord::new(2, 0), [100, 0, 0]),
+            (Coord::new(1, 3), [200, 0, 0]),
+            (Coord::new(3, 3), [0, 0, 0]),
+        ];
+        for (coord, expected) in cases {
+            assert_eq!(pixel(&image, coord), expected, "{}", coord);
+        }
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let grid = Grid::from_rows(vec![vec![[1, 2, 3], [4, 5, 6]]]).unwrap();
+        let mut out = vec![];
+        Image::from_grid(&grid, 1, |colour| *colour)
+            .write_ppm(&mut out)
+            .unwrap();
+        assert_eq!(out, b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+    }
+
+    #[test]
+    fn test_rgb() {
+        let cases = vec![
+            (Color::Red, [255, 0, 0]),
+            (Color::AnsiValue(8), [128, 128, 128]),
+            (Color::AnsiValue(196), [255, 0, 0]),
+            (Color::AnsiValue(244), [128, 128, 128]),
+            (Color::Rgb { r: 1, g: 2, b: 3 }, [1, 2, 3]),
+        ];
+        for (colour, expected) in cases {
+            assert_eq!(rgb(colour), expected, "{:?}", colour);
+        }
+    }
+
+    #[test]
+    fn test_sequence() {
+        let directory = env::temp_dir().join(format!("aoc24-images-{}", std::process::id()));
+        let mut sequence = Sequence::create(&directory, false, 3).unwrap();
+        for _ in 0..2 {
+            assert_eq!(sequence.draw(&text_frame("#.")), Flow::Continue);
+        }
+        assert_eq!(sequence.finish().unwrap(), 2);
+        let second = fs::read(directory.join("00002.ppm")).unwrap();
+        assert!(second.starts_with(b"P6\n6 3\n255\n"));
+        assert_eq!(second.len(), b"P6\n6 3\n255\n".len() + 6 * 3 * 3);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
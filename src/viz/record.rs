@@ -0,0 +1,238 @@
+use crate::utils::Coord;
+use crate::viz::{write_frame, Canvas, Flow, Frame};
+use crossterm::style::Print;
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, queue};
+use std::fs::{self, File};
+use std::io::{self, stdout, BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// An asciinema v2 `.cast` file
+    Asciicast,
+    /// Numbered frames of plain text, each under a `--- frame N at T: caption ---` header
+    Text,
+}
+
+impl Format {
+    fn of(path: &Path) -> Format {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("cast") => Format::Asciicast,
+            _ => Format::Text,
+        }
+    }
+}
+
+/// Writes every frame drawn on it to a file, each shown for the same delay.
+pub struct Recorder {
+    out: BufWriter<File>,
+    format: Format,
+    delay: Duration,
+    frames: u32,
+    error: Option<io::Error>,
+}
+
+impl Recorder {
+    /// Records to `path` as an asciicast if it ends in `.cast`, or as a plain-text frame dump.
+    pub fn create(path: &Path, delay: Duration) -> io::Result<Recorder> {
+        Ok(Recorder {
+            out: BufWriter::new(File::create(path)?),
+            format: Format::of(path),
+            delay,
+            frames: 0,
+            error: None,
+        })
+    }
+
+    /// Flushes the recording, returning any error that stopped it.
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => self.out.flush(),
+        }
+    }
+
+    fn write(&mut self, frame: &Frame) -> io::Result<()> {
+        let time = (self.delay * self.frames).as_secs_f64();
+        self.frames += 1;
+        match self.format {
+            Format::Asciicast => {
+                let size = Coord::new(frame.cells.width(), frame.cells.height());
+                if self.frames == 1 {
+                    let header = serde_json::json!({
+                        "version": 2,
+                        "width": size.x.max(frame.caption.chars().count() as i64),
+                        "height": size.y + 1,
+                    });
+                    writeln!(self.out, "{}", header)?;
+                }
+                let mut data = vec![];
+                write_frame(&mut data, frame, Coord::ORIGIN, size)?;
+                queue!(
+                    data,
+                    cursor::MoveTo(0, size.y as u16),
+                    Print(&frame.caption)
+                )?;
+                let event = (time, "o", String::from_utf8_lossy(&data));
+                writeln!(self.out, "{}", serde_json::to_string(&event)?)
+            }
+            Format::Text => write!(
+                self.out,
+                "--- frame {} at {:.3}s: {} ---\n{}",
+                self.frames,
+                time,
+                frame.caption,
+                frame.text()
+            ),
+        }
+    }
+}
+
+impl Canvas for Recorder {
+    fn draw(&mut self, frame: &Frame) -> Flow {
+        if self.error.is_some() {
+            return Flow::Stop;
+        }
+        match self.write(frame) {
+            Ok(()) => Flow::Continue,
+            Err(err) => {
+                self.error = Some(err);
+                Flow::Stop
+            }
+        }
+    }
+}
+
+/// A recorded screen and when it was shown.
+#[derive(Debug, PartialEq)]
+pub struct Screen {
+    pub time: Duration,
+    pub output: String,
+}
+
+/// Reads the screens from a recording.
+pub fn load(path: &Path) -> io::Result<Vec<Screen>> {
+    let contents = fs::read_to_string(path)?;
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    match Format::of(path) {
+        Format::Asciicast => contents
+            .lines()
+            .skip(1)
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (time, kind, output): (f64, String, String) = serde_json::from_str(line)?;
+                Ok((kind == "o").then(|| Screen {
+                    time: Duration::from_secs_f64(time),
+                    output,
+                }))
+            })
+            .filter_map(Result::transpose)
+            .collect(),
+        Format::Text => {
+            let mut screens: Vec<Screen> = vec![];
+            for line in contents.lines() {
+                let header = line
+                    .strip_prefix("--- frame ")
+                    .and_then(|header| header.strip_suffix(" ---"));
+                match (header, screens.last_mut()) {
+                    (Some(header), _) => {
+                        let time = header
+                            .split_once(" at ")
+                            .and_then(|(_, rest)| rest.split_once("s:"))
+                            .and_then(|(time, _)| time.parse::<f64>().ok())
+                            .ok_or_else(|| invalid(format!("Bad frame header '{}'", line)))?;
+                        screens.push(Screen {
+                            time: Duration::from_secs_f64(time),
+                            output: line.to_string() + "\n",
+                        });
+                    }
+                    (None, Some(screen)) => {
+                        screen.output.push_str(line);
+                        screen.output.push('\n');
+                    }
+                    (None, None) => return Err(invalid("Expected a frame header".to_string())),
+                }
+            }
+            Ok(screens)
+        }
+    }
+}
+
+/// Plays a recording back on stdout, `speed` times faster than it was recorded. Screens are
+/// printed one after another without waiting when stdout isn't a terminal.
+pub fn replay(path: &Path, speed: f64) -> io::Result<()> {
+    let screens = load(path)?;
+    let mut stdout = stdout();
+    let live = stdout.is_terminal();
+    let start = Instant::now();
+    for screen in screens {
+        if live {
+            let due = screen.time.div_f64(speed.max(f64::EPSILON));
+            sleep(due.saturating_sub(start.elapsed()));
+            if Format::of(path) == Format::Text {
+                queue!(
+                    stdout,
+                    terminal::Clear(ClearType::All),
+                    cursor::MoveTo(0, 0)
+                )?;
+            }
+        }
+        // Raw mode isn't on, so lines need a carriage return to start at the left
+        let output = match live {
+            true => screen.output.replace('\n', "\r\n"),
+            false => screen.output,
+        };
+        queue!(stdout, Print(output))?;
+        stdout.flush()?;
+    }
+    writeln!(stdout)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::viz::test::text_frame;
+    use std::env;
+
+    fn record(name: &str) -> Vec<Screen> {
+        let path = env::temp_dir().join(format!("aoc24-{}-{}", std::process::id(), name));
+        let mut recorder = Recorder::create(&path, Duration::from_millis(50)).unwrap();
+        for caption in ["first", "second"] {
+            let frame = text_frame("ab\ncd").caption(caption);
+            assert_eq!(recorder.draw(&frame), Flow::Continue);
+        }
+        recorder.finish().unwrap();
+        let screens = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        screens
+    }
+
+    #[test]
+    fn test_text_dump() {
+        assert_eq!(
+            record("frames.txt"),
+            vec![
+                Screen {
+                    time: Duration::ZERO,
+                    output: "--- frame 1 at 0.000s: first ---\nab\ncd\n".to_string()
+                },
+                Screen {
+                    time: Duration::from_millis(50),
+                    output: "--- frame 2 at 0.050s: second ---\nab\ncd\n".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_asciicast() {
+        let screens = record("frames.cast");
+        assert_eq!(screens.len(), 2);
+        assert_eq!(screens[1].time, Duration::from_millis(50));
+        assert!(screens[1].output.contains("ab"));
+        assert!(screens[1].output.ends_with("second"));
+    }
+}